@@ -0,0 +1,90 @@
+// Shape errors reported by the try_* methods. Shapes are (rows, cols).
+// Decompositions that already return Result<_, LinalgError> report these as
+// LinalgError::Shape instead. The gemm/gemv/ger/syrk kernels, the symbolic sparse
+// analyses and the iterative solvers keep their asserts: they are the layer below
+// the checked Matrix API, and a shape mismatch there is a bug in the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixError {
+    ZeroDimension {
+        rows: usize,
+        cols: usize,
+    },
+    SizeOverflow {
+        rows: usize,
+        cols: usize,
+    },
+    LengthMismatch {
+        len: usize,
+        rows: usize,
+        cols: usize,
+    },
+    ReshapeMismatch {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    ShapeMismatch {
+        op: &'static str,
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+    },
+    NotSquare {
+        shape: (usize, usize),
+    },
+    NotVector {
+        shape: (usize, usize),
+    },
+    NotScalar {
+        shape: (usize, usize),
+    },
+    InvalidSparse {
+        reason: &'static str,
+    },
+    EmptyStack,
+}
+
+impl std::fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatrixError::ZeroDimension { rows, cols } => write!(
+                f,
+                "Rows and columns cannot be set to zero, got ({}, {}).",
+                rows, cols
+            ),
+            MatrixError::SizeOverflow { rows, cols } => {
+                write!(f, "The matrix size ({}, {}) overflows usize.", rows, cols)
+            }
+            MatrixError::LengthMismatch { len, rows, cols } => write!(
+                f,
+                "The array length {} must be equal the matrix size ({}, {}).",
+                len, rows, cols
+            ),
+            MatrixError::ReshapeMismatch { from, to } => write!(
+                f,
+                "({}, {}) cannot reshape to ({}, {}).",
+                from.0, from.1, to.0, to.1
+            ),
+            MatrixError::ShapeMismatch { op, lhs, rhs } => write!(
+                f,
+                "Shapes ({}, {}) and ({}, {}) are incompatible for {}.",
+                lhs.0, lhs.1, rhs.0, rhs.1, op
+            ),
+            MatrixError::NotSquare { shape } => write!(
+                f,
+                "Expected a square matrix, got ({}, {}).",
+                shape.0, shape.1
+            ),
+            MatrixError::NotVector { shape } => {
+                write!(f, "Expected a vector, got ({}, {}).", shape.0, shape.1)
+            }
+            MatrixError::NotScalar { shape } => {
+                write!(f, "Expected a scalar, got ({}, {}).", shape.0, shape.1)
+            }
+            MatrixError::InvalidSparse { reason } => {
+                write!(f, "Invalid compressed sparse structure: {}", reason)
+            }
+            MatrixError::EmptyStack => write!(f, "Cannot stack an empty list of matrices."),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
@@ -1,8 +1,13 @@
+pub mod error;
+pub mod linalg;
 pub mod ops;
 pub mod rand;
+pub mod solver;
 pub mod sparse;
 pub mod tensor;
+pub mod view;
 
+use crate::error::MatrixError;
 use std::cmp::PartialEq;
 
 #[derive(Clone, Debug)]
@@ -48,43 +53,54 @@ impl<T> Matrix<T> {
     }
 
     pub fn from_vec(array: Vec<T>, rows: usize, cols: usize) -> Self {
-        assert!(
-            rows != 0 && cols != 0,
-            "Rows and columns cannot be set to zero."
-        );
-        assert_eq!(
-            array.len(),
-            rows * cols,
-            "The array length must be equal the matrix size."
-        );
-        Self { rows, cols, array }
+        Self::try_from_vec(array, rows, cols).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn from_vec2d(array: Vec<Vec<T>>, rows: usize, cols: usize) -> Self {
-        assert!(
-            rows != 0 && cols != 0,
-            "Rows and columns cannot be set to zero."
-        );
-        assert_eq!(
-            array.iter().flatten().collect::<Vec<_>>().len(),
-            rows * cols,
-            "The array length must be equal the matrix size."
-        );
-        Self {
-            rows,
-            cols,
-            array: array.into_iter().flatten().collect(),
+    pub fn try_from_vec(array: Vec<T>, rows: usize, cols: usize) -> Result<Self, MatrixError> {
+        if rows == 0 || cols == 0 {
+            return Err(MatrixError::ZeroDimension { rows, cols });
+        }
+        let size = rows
+            .checked_mul(cols)
+            .ok_or(MatrixError::SizeOverflow { rows, cols })?;
+        if array.len() != size {
+            return Err(MatrixError::LengthMismatch {
+                len: array.len(),
+                rows,
+                cols,
+            });
         }
+        Ok(Self { rows, cols, array })
+    }
+
+    pub fn from_vec2d(array: Vec<Vec<T>>, rows: usize, cols: usize) -> Self {
+        Self::try_from_vec2d(array, rows, cols).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_vec2d(
+        array: Vec<Vec<T>>,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Self, MatrixError> {
+        Self::try_from_vec(array.into_iter().flatten().collect(), rows, cols)
     }
 
     pub fn from_vec_row(array: Vec<T>) -> Self {
+        Self::try_from_vec_row(array).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_vec_row(array: Vec<T>) -> Result<Self, MatrixError> {
         let cols = array.len();
-        Self::from_vec(array, 1, cols)
+        Self::try_from_vec(array, 1, cols)
     }
 
     pub fn from_vec_col(array: Vec<T>) -> Self {
+        Self::try_from_vec_col(array).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_vec_col(array: Vec<T>) -> Result<Self, MatrixError> {
         let rows = array.len();
-        Self::from_vec(array, rows, 1)
+        Self::try_from_vec(array, rows, 1)
     }
 
     pub fn to_slice(&self) -> &[T] {
@@ -117,18 +133,37 @@ impl<T> Matrix<T> {
         self.rows == 1 && self.cols == 1
     }
 
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub(crate) fn check_square(&self) -> Result<(), MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::NotSquare {
+                shape: self.shape(),
+            });
+        }
+        Ok(())
+    }
+
     pub fn reshape(&mut self, rows: usize, cols: usize) {
-        assert_eq!(
-            self.rows * self.cols,
-            rows * cols,
-            "({}, {}) cannot reshape to ({}, {})",
-            self.rows,
-            self.cols,
-            rows,
-            cols
-        );
+        self.try_reshape(rows, cols)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_reshape(&mut self, rows: usize, cols: usize) -> Result<(), MatrixError> {
+        let size = rows
+            .checked_mul(cols)
+            .ok_or(MatrixError::SizeOverflow { rows, cols })?;
+        if self.array.len() != size {
+            return Err(MatrixError::ReshapeMismatch {
+                from: self.shape(),
+                to: (rows, cols),
+            });
+        }
         self.rows = rows;
         self.cols = cols;
+        Ok(())
     }
 }
 
@@ -138,13 +173,14 @@ where
     T: Clone,
 {
     pub fn as_shape(&self, rows: usize, cols: usize) -> Self {
-        assert_eq!(self.rows * self.cols, rows * cols);
+        self.try_as_shape(rows, cols)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        Self {
-            rows,
-            cols,
-            array: self.array.clone(),
-        }
+    pub fn try_as_shape(&self, rows: usize, cols: usize) -> Result<Self, MatrixError> {
+        let mut ret = self.clone();
+        ret.try_reshape(rows, cols)?;
+        Ok(ret)
     }
 
     pub fn from_slice<const C: usize>(data: &[[T; C]]) -> Self {
@@ -165,8 +201,16 @@ where
     }
 
     pub fn as_scalar(&self) -> Option<T> {
-        assert!(self.is_scalar());
-        self.array.get(0).cloned()
+        self.try_as_scalar().ok()
+    }
+
+    pub fn try_as_scalar(&self) -> Result<T, MatrixError> {
+        if !self.is_scalar() {
+            return Err(MatrixError::NotScalar {
+                shape: self.shape(),
+            });
+        }
+        Ok(self.array[0].clone())
     }
 
     pub fn transpose(&self) -> Self {
@@ -204,29 +248,38 @@ where
     }
 
     pub fn concat(&self, rhs: &Matrix<T>, ax: Axis) -> Self {
-        match ax {
-            Axis::ROW => {
-                assert_eq!(self.cols, rhs.cols);
-                Self {
-                    rows: self.rows + rhs.rows,
-                    cols: self.cols,
-                    array: [&self.array[..], &rhs.array[..]].concat(),
-                }
-            }
-            Axis::COLUMN => {
-                assert_eq!(self.rows, rhs.rows);
-                Self {
-                    rows: self.rows,
-                    cols: self.cols + rhs.cols,
-                    array: self
-                        .array
-                        .chunks(self.rows)
-                        .zip(rhs.array.chunks(rhs.rows))
-                        .flat_map(|(s, t)| [s, t].concat())
-                        .collect(),
-                }
-            }
+        self.try_concat(rhs, ax).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_concat(&self, rhs: &Matrix<T>, ax: Axis) -> Result<Self, MatrixError> {
+        let mismatch = match ax {
+            Axis::ROW => self.cols != rhs.cols,
+            Axis::COLUMN => self.rows != rhs.rows,
+        };
+        if mismatch {
+            return Err(MatrixError::ShapeMismatch {
+                op: "concat",
+                lhs: self.shape(),
+                rhs: rhs.shape(),
+            });
         }
+        Ok(match ax {
+            Axis::ROW => Self {
+                rows: self.rows + rhs.rows,
+                cols: self.cols,
+                array: [&self.array[..], &rhs.array[..]].concat(),
+            },
+            Axis::COLUMN => Self {
+                rows: self.rows,
+                cols: self.cols + rhs.cols,
+                array: self
+                    .array
+                    .chunks(self.cols)
+                    .zip(rhs.array.chunks(rhs.cols))
+                    .flat_map(|(s, t)| [s, t].concat())
+                    .collect(),
+            },
+        })
     }
 }
 
@@ -270,7 +323,55 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{sparse::SparseMatrix, Axis, Matrix};
+    use crate::{
+        error::MatrixError,
+        linalg::LinalgError,
+        ops::gemm::{gemm, gemv, ger, syrk},
+        ops::norm::Norm,
+        solver::{
+            self,
+            precond::{Ic0, Identity, Ilu0, Jacobi, Preconditioner, Ssor},
+            Options, Status,
+        },
+        sparse::{
+            cholesky::{CholeskySymbolic, SparseCholesky},
+            coo::CooMatrix,
+            csc::CscMatrix,
+            lu::{LuSymbolic, SparseLu},
+            SparseMatrix,
+        },
+        tensor::{
+            einsum,
+            einsum::{EinsumError, EinsumOperand},
+            Tensor,
+        },
+        Axis, Matrix,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn poisson(n: usize) -> SparseMatrix<f64> {
+        let mut coo = CooMatrix::new(n, n);
+        for i in 0..n {
+            coo.push(i, i, 2.);
+            if i > 0 {
+                coo.push(i, i - 1, -1.);
+            }
+            if i + 1 < n {
+                coo.push(i, i + 1, -1.);
+            }
+        }
+        coo.to_csr()
+    }
+
+    fn approx_eq(x: &Matrix<f64>, y: &Matrix<f64>) -> bool {
+        x.rows == y.rows
+            && x.cols == y.cols
+            && x.array
+                .iter()
+                .zip(y.array.iter())
+                .all(|(a, b)| (a - b).abs() < 1e-9)
+    }
 
     #[test]
     fn to_slice() {
@@ -559,6 +660,11 @@ mod tests {
                 [7., 8., 9., 3., 2., 1.],
             ])
         );
+        let y = Matrix::new_col_vector([0., 1., 2.]);
+        assert_eq!(
+            x.concat(&y, Axis::COLUMN),
+            Matrix::new([[1., 2., 3., 0.], [4., 5., 6., 1.], [7., 8., 9., 2.]])
+        );
     }
 
     #[test]
@@ -758,15 +864,1302 @@ mod tests {
         assert_eq!(&x * &v, y);
         assert_eq!(x.clone() * &v, y);
         assert_eq!(&x * v.clone(), y);
-        assert_eq!(x * v.clone(), y);
+        assert_eq!(x.clone() * v.clone(), y);
 
-        let v = v.transpose();
-        let y = Matrix::new([[10., 4., 6., 6.]]);
-        /*
+        let v = Matrix::new([[1., 2., 3.]]);
+        let y = Matrix::new([[10., 4., 2., 6.]]);
         assert_eq!(&v * &x, y);
         assert_eq!(&v * x.clone(), y);
         assert_eq!(v.clone() * &x, y);
-        assert_eq!(x * v, y);
-        */
+        assert_eq!(v * x.clone(), y);
+
+        // multi-column SpMM and dense x sparse agree with the dense product
+        let d = Matrix::new([[1., -1.], [2., 0.], [0., 3.], [1., 1.]]);
+        assert_eq!(&x * &d, x.to_dence() * &d);
+        assert_eq!(x.clone() * d.clone(), x.to_dence() * &d);
+        let e = Matrix::new([[1., 0., 2.], [-1., 1., 0.]]);
+        assert_eq!(&e * &x, &e * x.to_dence());
+        assert_eq!(e.clone() * x.clone(), &e * x.to_dence());
+    }
+
+    #[test]
+    fn lu() {
+        let a = Matrix::new([[2., 1., 1.], [4., -6., 0.], [-2., 7., 2.]]);
+        let lu = a.lu();
+        assert!(!lu.is_singular());
+        assert!(approx_eq(&(lu.p() * &a), &(lu.l() * lu.u())));
+        assert!((a.det() + 16.).abs() < 1e-9);
+
+        let b1 = Matrix::new_col_vector([5., -2., 9.]);
+        let b2 = Matrix::new_col_vector([1., 0., 0.]);
+        let x = a.solve(&b1.concat(&b2, Axis::COLUMN)).unwrap();
+        assert!(approx_eq(&(&a * &x), &b1.concat(&b2, Axis::COLUMN)));
+
+        let inv = a.inverse().unwrap();
+        assert!(approx_eq(&(&a * &inv), &Matrix::eye(3)));
+
+        let s = Matrix::new([[1., 2.], [2., 4.]]);
+        assert!(s.lu().is_singular());
+        assert_eq!(s.inverse(), Err(LinalgError::Singular));
+
+        // a negligible pivot leaves no stale, unscaled entries in L
+        let t = Matrix::new([[1e20, 0., 0.], [0., 2., 0.], [0., 1., 1.]]);
+        let lu = t.lu();
+        assert!(lu.is_singular());
+        assert_eq!(lu.l(), Matrix::eye(3));
+    }
+
+    #[test]
+    fn qr() {
+        let a = Matrix::new([
+            [12., -51., 4.],
+            [6., 167., -68.],
+            [-4., 24., -41.],
+            [1., 1., 1.],
+        ]);
+        let qr = a.qr();
+        assert_eq!(qr.q().rows(), 4);
+        assert_eq!(qr.q().cols(), 3);
+        assert!(approx_eq(&(qr.q() * qr.r()), &a));
+        assert!(approx_eq(&(qr.q_full() * qr.r_full()), &a));
+        assert!(approx_eq(
+            &(qr.q_full().transpose() * qr.q_full()),
+            &Matrix::eye(4)
+        ));
+
+        let qr = a.qr_pivoted();
+        assert!(approx_eq(&(qr.q() * qr.r()), &(&a * &qr.p())));
+    }
+
+    #[test]
+    fn lstsq() {
+        let a = Matrix::new([[1., 1.], [1., 2.], [1., 3.], [1., 4.]]);
+        let b = Matrix::new_col_vector([3., 5., 7., 9.]);
+        assert!(approx_eq(&a.lstsq(&b), &Matrix::new_col_vector([1., 2.])));
+
+        // the second column is twice the first one
+        let a = Matrix::new([[1., 2., 1.], [1., 2., 2.], [1., 2., 3.], [1., 2., 4.]]);
+        let qr = a.qr_pivoted();
+        assert_eq!(qr.rank(), 2);
+        let x = qr.lstsq(&b).unwrap();
+        assert!(approx_eq(&(&a * &x), &b));
+        assert_eq!(a.qr().lstsq(&b), Err(LinalgError::Singular));
+        let full = Matrix::new([[1., 1.], [1., 2.], [1., 3.], [1., 4.]]);
+        assert!(approx_eq(
+            &full.qr().lstsq(&b).unwrap(),
+            &Matrix::new_col_vector([1., 2.])
+        ));
+    }
+
+    #[test]
+    fn cholesky() {
+        let a = Matrix::new([[4., 12., -16.], [12., 37., -43.], [-16., -43., 98.]]);
+        let chol = a.cholesky().unwrap();
+        assert!(approx_eq(
+            &chol.l(),
+            &Matrix::new([[2., 0., 0.], [6., 1., 0.], [-8., 5., 3.]])
+        ));
+        let b = Matrix::new_col_vector([1., 2., 3.]);
+        assert!(approx_eq(&(&a * chol.solve(&b)), &b));
+        assert!(approx_eq(&(&a * chol.inverse()), &Matrix::eye(3)));
+        assert!((chol.log_det() - 36_f64.ln()).abs() < 1e-9);
+
+        let s = Matrix::new([[1., 2.], [2., 1.]]);
+        assert_eq!(s.cholesky().unwrap_err(), LinalgError::NotPositiveDefinite);
+    }
+
+    #[test]
+    fn ldlt() {
+        let a = Matrix::new([[0., 1., 2.], [1., 0., 3.], [2., 3., 0.]]);
+        let ldlt = a.ldlt();
+        let p = ldlt.p();
+        assert!(approx_eq(
+            &(&p * &a * p.transpose()),
+            &(ldlt.l() * ldlt.d() * ldlt.l().transpose())
+        ));
+        assert!((ldlt.det() - a.det()).abs() < 1e-9);
+        let b = Matrix::new_col_vector([1., 2., 3.]);
+        assert!(approx_eq(&(&a * ldlt.solve(&b).unwrap()), &b));
+
+        // positive semi-definite
+        let a = Matrix::new([[1., 1.], [1., 1.]]);
+        let ldlt = a.ldlt();
+        assert!(approx_eq(
+            &(ldlt.p() * &a * ldlt.p().transpose()),
+            &(ldlt.l() * ldlt.d() * ldlt.l().transpose())
+        ));
+        assert_eq!(
+            ldlt.solve(&Matrix::new_col_vector([1., 1.])).unwrap_err(),
+            LinalgError::Singular
+        );
+
+        // rank 2 PSD: the last pivot only survives as rounding noise
+        let f = Matrix::new([[0.1, 0.7], [0.3, 0.2], [0.9, 1.3]]);
+        let a = &f * &f.transpose();
+        let ldlt = a.ldlt();
+        assert!(ldlt.is_singular());
+        assert_eq!(ldlt.det(), 0.);
+        assert_eq!(
+            ldlt.solve(&Matrix::new_col_vector([1., 1., 1.]))
+                .unwrap_err(),
+            LinalgError::Singular
+        );
+    }
+
+    #[test]
+    fn eigh() {
+        // path graph Laplacian
+        let a = Matrix::new([[1., -1., 0.], [-1., 2., -1.], [0., -1., 1.]]);
+        let eig = a.eigh().unwrap();
+        assert!(approx_eq(
+            eig.values(),
+            &Matrix::new_col_vector([0., 1., 3.])
+        ));
+        let v = eig.vectors();
+        assert!(approx_eq(&(v.transpose() * v), &Matrix::eye(3)));
+        assert!(approx_eq(&(v * eig.values().diag() * v.transpose()), &a));
+        assert!(approx_eq(&a.eigvalsh().unwrap(), eig.values()));
+
+        let a = Matrix::new([[2.]]);
+        assert!(approx_eq(a.eigh().unwrap().values(), &Matrix::new([[2.]])));
+    }
+
+    #[test]
+    fn svd() {
+        let a = Matrix::new([[3., 2., 2.], [2., 3., -2.]]);
+        let svd = a.svd();
+        assert!(approx_eq(svd.s(), &Matrix::new_col_vector([5., 3.])));
+        assert!(approx_eq(
+            &(svd.u() * svd.s().diag() * svd.v().transpose()),
+            &a
+        ));
+
+        let svd = a.svd_full();
+        assert_eq!(svd.v().rows(), 3);
+        assert_eq!(svd.v().cols(), 3);
+        assert!(approx_eq(&(svd.v().transpose() * svd.v()), &Matrix::eye(3)));
+
+        assert_eq!(a.rank(), 2);
+        assert!((a.cond() - 5. / 3.).abs() < 1e-9);
+        assert!(approx_eq(&(&a * a.pinv(None)), &Matrix::eye(2)));
+        assert!(approx_eq(
+            &(&a * a.null_space().unwrap()),
+            &Matrix::zero(2, 1)
+        ));
+        assert!(a.transpose().null_space().is_none());
+        assert_eq!(a.range().unwrap().cols(), 2);
+
+        // rank deficient
+        let a = Matrix::<f64>::new([[1., 2.], [2., 4.], [3., 6.]]);
+        assert_eq!(a.rank(), 1);
+        assert!(a.cond().is_infinite() || a.cond() > 1e12);
+        let p = a.pinv(None);
+        assert!(approx_eq(&(&(&a * &p) * &a), &a));
+        assert_eq!(a.range().unwrap().cols(), 1);
+        assert_eq!(a.null_space().unwrap().cols(), 1);
+        let z = Matrix::<f64>::zero(3, 2);
+        assert!(z.range().is_none());
+        assert_eq!(z.null_space().unwrap().cols(), 2);
+    }
+
+    #[test]
+    fn schur() {
+        let a = Matrix::new([
+            [4., 1., -2., 2.],
+            [1., 2., 0., 1.],
+            [-2., 0., 3., -2.],
+            [2., 1., -2., -1.],
+        ]);
+        let hess = a.hessenberg();
+        assert!(approx_eq(&(hess.q() * hess.h() * hess.q().transpose()), &a));
+        assert_eq!(hess.h()[3][0], 0.);
+        assert_eq!(hess.h()[3][1], 0.);
+
+        let a = Matrix::new([[0., -1., 0.], [1., 0., 0.], [0., 0., 2.]]);
+        let schur = a.schur().unwrap();
+        assert!(approx_eq(
+            &(schur.z() * schur.t() * schur.z().transpose()),
+            &a
+        ));
+        assert!(approx_eq(
+            &(schur.z().transpose() * schur.z()),
+            &Matrix::eye(3)
+        ));
+    }
+
+    #[test]
+    fn eig() {
+        // rotation by 90 degrees with an additional real eigenvalue
+        let a = Matrix::<f64>::new([[0., -1., 0.], [1., 0., 0.], [0., 0., 2.]]);
+        let eig = a.eig().unwrap();
+        assert!(!eig.is_real());
+        let mut values: Vec<_> = eig
+            .real()
+            .to_slice()
+            .iter()
+            .zip(eig.imag().to_slice())
+            .map(|(&re, &im)| ((re * 1e6).round() / 1e6, (im * 1e6).round() / 1e6))
+            .collect();
+        values.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        assert_eq!(values, vec![(0., -1.), (0., 1.), (2., 0.)]);
+        let v = eig.vectors().unwrap();
+        assert!(approx_eq(&(&a * v), &(v * eig.d())));
+
+        let a = Matrix::new([[2., 0., 0.], [1., 3., 0.], [4., 5., 6.]]);
+        let (re, im) = a.eigvals().unwrap();
+        let mut re = re.to_slice().to_vec();
+        re.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        assert!(approx_eq(
+            &Matrix::from_vec_col(re),
+            &Matrix::new_col_vector([2., 3., 6.])
+        ));
+        assert_eq!(im, Matrix::zero(3, 1));
+    }
+
+    #[test]
+    fn norm() {
+        let x = Matrix::new([[1., -2.], [-3., 4.]]);
+        assert_eq!(x.norm(Norm::Frobenius), 30_f64.sqrt());
+        assert_eq!(x.norm(Norm::One), 6.);
+        assert_eq!(x.norm(Norm::Inf), 7.);
+        assert_eq!(x.norm(Norm::MaxAbs), 4.);
+        let s = x.singular_values();
+        assert!((x.norm(Norm::Spectral) - s[0][0]).abs() < 1e-12);
+        assert!((x.norm(Norm::Nuclear) - (s[0][0] + s[1][0])).abs() < 1e-12);
+
+        assert_eq!(x.norm1(Some(Axis::ROW)), Matrix::new_col_vector([3., 7.]));
+        assert_eq!(
+            x.norm1(Some(Axis::COLUMN)),
+            Matrix::new_row_vector([4., 6.])
+        );
+        assert_eq!(
+            x.norm2(Some(Axis::ROW)),
+            Matrix::new_col_vector([5_f64.sqrt(), 5.])
+        );
+        assert_eq!(x.norm_max(None), Matrix::new([[4.]]));
+    }
+
+    #[test]
+    fn trace() {
+        let x = Matrix::new([[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+        assert_eq!(x.trace(), 15);
+        let x = Matrix::<f64>::new([[1., 2.], [3., 4.]]);
+        assert!((x.det() + 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sparse_transpose() {
+        let x = SparseMatrix::new([[1., 0., 0., 0.], [0., 0., 0., 0.], [3., 0., 0., 2.]]);
+        assert_eq!(
+            x.to_dence(),
+            Matrix::new([[1., 0., 0., 0.], [0., 0., 0., 0.], [3., 0., 0., 2.]])
+        );
+        assert_eq!(x.transpose(), x.to_dence().transpose().to_sparse());
+        assert_eq!(x.transpose().transpose(), x);
+
+        let csc = x.to_csc();
+        assert_eq!(csc.col_ptr(), &[0, 2, 2, 2, 3]);
+        assert_eq!(csc.row_idx(), &[0, 2, 2]);
+        assert_eq!(csc.to_dence(), x.to_dence());
+        assert_eq!(csc.to_csr(), x);
+
+        let y = SparseMatrix::from_csr(3, 4, vec![0, 1, 1, 3], vec![0, 0, 3], vec![1., 3., 2.]);
+        assert_eq!(y, x);
+        let unsorted =
+            SparseMatrix::try_from_csr(3, 4, vec![0, 1, 1, 3], vec![0, 3, 0], vec![1., 2., 3.]);
+        assert!(matches!(unsorted, Err(MatrixError::InvalidSparse { .. })));
+        let repeated =
+            SparseMatrix::try_from_csr(3, 4, vec![0, 1, 1, 3], vec![0, 3, 3], vec![1., 2., 3.]);
+        assert!(matches!(repeated, Err(MatrixError::InvalidSparse { .. })));
+        // a repeated index across rows is fine
+        assert!(CscMatrix::try_from_csc(
+            3,
+            4,
+            vec![0, 2, 2, 2, 3],
+            vec![0, 2, 2],
+            vec![1., 3., 2.]
+        )
+        .is_ok());
+        assert!(SparseMatrix::try_from_csr(
+            3,
+            4,
+            vec![0, 1, 1, 3],
+            vec![0, 0, 4],
+            vec![1., 3., 2.]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn coo() {
+        let mut coo = CooMatrix::new(3, 4);
+        coo.push(2, 3, 1.);
+        coo.push(0, 0, 1.);
+        coo.push(2, 0, 3.);
+        coo.push(2, 3, 1.);
+        assert_eq!(coo.nnz(), 4);
+        let x = coo.to_csr();
+        assert_eq!(
+            x,
+            SparseMatrix::new([[1., 0., 0., 0.], [0., 0., 0., 0.], [3., 0., 0., 2.]])
+        );
+        assert_eq!(coo.to_csc(), x.to_csc());
+        assert_eq!(x.to_coo().to_csr(), x);
+    }
+
+    #[test]
+    fn sparse_arith() {
+        let x = SparseMatrix::new([[1., 0., 2.], [0., 3., 0.]]);
+        let y = SparseMatrix::new([[0., 1., -2.], [4., 0., 0.]]);
+        assert_eq!(&x + &y, SparseMatrix::new([[1., 1., 0.], [4., 3., 0.]]));
+        assert_eq!(
+            x.clone() - &y,
+            SparseMatrix::new([[1., -1., 4.], [-4., 3., 0.]])
+        );
+        assert_eq!(&x - &x, SparseMatrix::new([[0., 0., 0.], [0., 0., 0.]]));
+        assert_eq!(
+            x.cwise_mul(&y),
+            SparseMatrix::new([[0., 0., -4.], [0., 0., 0.]])
+        );
+        assert_eq!(-&x, SparseMatrix::new([[-1., 0., -2.], [0., -3., 0.]]));
+        assert_eq!(&x * 2., SparseMatrix::new([[2., 0., 4.], [0., 6., 0.]]));
+        assert_eq!(
+            2. * x.clone(),
+            SparseMatrix::new([[2., 0., 4.], [0., 6., 0.]])
+        );
+
+        let z = SparseMatrix::new([[1., 0.], [0., 1.], [1., 1.]]);
+        assert_eq!(&x * &z, (x.to_dence() * z.to_dence()).to_sparse());
+        assert_eq!(x * z, SparseMatrix::new([[3., 2.], [0., 3.]]));
+    }
+
+    #[test]
+    fn krylov() {
+        let a = poisson(50);
+        let b = Matrix::one(50, 1);
+        let opts = Options {
+            tol: 1e-10,
+            max_iter: 200,
+        };
+
+        let res = solver::cg(&a, &b, None, &Identity, &opts);
+        assert_eq!(res.status, Status::Converged);
+        assert_eq!(res.iterations, res.residuals.len());
+        assert!(approx_eq(&(&a * &res.x), &b));
+
+        let res = solver::bicgstab(&a, &b, None, &Identity, &opts);
+        assert!(res.converged());
+        assert!(approx_eq(&(&a * &res.x), &b));
+
+        let res = solver::gmres(&a, &b, None, &Identity, 50, &opts);
+        assert!(res.converged());
+        assert!(approx_eq(&(&a * &res.x), &b));
+
+        // dense operator, non-symmetric
+        let a = Matrix::new([[4., 1., 0.], [2., 5., 1.], [0., 1., 3.]]);
+        let b = Matrix::new_col_vector([1., 2., 3.]);
+        let res = solver::gmres(&a, &b, None, &Identity, 3, &opts);
+        assert!(res.converged());
+        assert!(approx_eq(&res.x, &a.solve(&b).unwrap()));
+
+        let opts = Options {
+            tol: 1e-10,
+            max_iter: 2,
+        };
+        let res = solver::cg(&poisson(50), &Matrix::one(50, 1), None, &Identity, &opts);
+        assert_eq!(res.status, Status::MaxIterations);
+        assert_eq!(res.residuals.len(), 2);
+    }
+
+    #[test]
+    fn preconditioner() {
+        let a = SparseMatrix::new([
+            [4., -1., 0., -1.],
+            [-1., 4., -1., 0.],
+            [0., -1., 4., -1.],
+            [-1., 0., -1., 4.],
+        ]);
+        let r = Matrix::new_col_vector([1., 2., 3., 4.]);
+
+        let jacobi = Jacobi::new(&a).unwrap();
+        assert_eq!(
+            jacobi.apply(&r),
+            Matrix::new_col_vector([0.25, 0.5, 0.75, 1.])
+        );
+
+        // on a tridiagonal matrix ILU(0) and IC(0) are exact
+        let t = poisson(6);
+        let b = Matrix::new_col_vector([1., 0., 2., 0., 3., 0.]);
+        let x = t.to_dence().solve(&b).unwrap();
+        assert!(approx_eq(&Ilu0::new(&t).unwrap().apply(&b), &x));
+        assert!(approx_eq(&Ic0::new(&t).unwrap().apply(&b), &x));
+        assert!(Ic0::new(&-&t).is_err());
+
+        // SSOR with omega = 1 is symmetric Gauss-Seidel
+        let ssor = Ssor::new(&a, 1.).unwrap();
+        let z = ssor.apply(&r);
+        let d = Matrix::new([
+            [4., 0., 0., 0.],
+            [0., 4., 0., 0.],
+            [0., 0., 4., 0.],
+            [0., 0., 0., 4.],
+        ]);
+        let l = Matrix::new([
+            [4., 0., 0., 0.],
+            [-1., 4., 0., 0.],
+            [0., -1., 4., 0.],
+            [-1., 0., -1., 4.],
+        ]);
+        let m = &l * d.inverse().unwrap() * l.transpose();
+        assert!(approx_eq(&(&m * &z), &r));
+
+        let a = poisson(100);
+        let b = Matrix::one(100, 1);
+        let opts = Options {
+            tol: 1e-10,
+            max_iter: 500,
+        };
+        let plain = solver::cg(&a, &b, None, &Identity, &opts);
+        let pre = solver::cg(&a, &b, None, &Ic0::new(&a).unwrap(), &opts);
+        assert!(pre.converged());
+        assert!(pre.iterations < plain.iterations);
+        let pre = solver::bicgstab(&a, &b, None, &Ilu0::new(&a).unwrap(), &opts);
+        assert!(pre.converged());
+        let pre = solver::gmres(&a, &b, None, &Ssor::new(&a, 1.5).unwrap(), 30, &opts);
+        assert!(pre.converged());
+        assert!(approx_eq(&(&a * &pre.x), &b));
+    }
+
+    #[test]
+    fn sparse_direct() {
+        // zero diagonal entries force row pivoting
+        let mut coo = CooMatrix::new(5, 5);
+        for (i, j, v) in [
+            (0, 1, 2.),
+            (0, 4, 1.),
+            (1, 0, 3.),
+            (1, 2, -1.),
+            (2, 1, 1.),
+            (2, 3, 4.),
+            (3, 2, 5.),
+            (3, 4, -2.),
+            (4, 0, 1.),
+            (4, 3, 1.),
+        ] {
+            coo.push(i, j, v);
+        }
+        let a = coo.to_csr();
+        let b = Matrix::new([[1., 0.], [2., 1.], [3., 0.], [4., 1.], [5., 0.]]);
+        let lu = a.lu(None, 1.).unwrap();
+        assert!(approx_eq(&(&a.to_dence() * lu.solve(&b)), &b));
+        let pa = a.permute(&lu.row_perm(), lu.col_perm()).to_dence();
+        let l = lu.l().to_dence();
+        let u = lu.u().to_dence();
+        assert!(approx_eq(&(&l * &u), &pa));
+
+        let q = [4, 3, 2, 1, 0];
+        let symbolic = LuSymbolic::new(&a, Some(&q));
+        let lu = SparseLu::factorize(&symbolic, &a, 0.1).unwrap();
+        assert!(approx_eq(&(&a.to_dence() * lu.solve(&b)), &b));
+        let mut lu = lu;
+        let a2 = &a * 2.;
+        lu.refactorize(&a2).unwrap();
+        assert!(approx_eq(&(&a2.to_dence() * lu.solve(&b)), &b));
+        assert!(lu.l().nnz() <= symbolic.lnz() && lu.u().nnz() <= symbolic.unz());
+        assert_eq!(
+            lu.refactorize(&SparseMatrix::new([[1., 0.], [0., 1.]]))
+                .err(),
+            Some(LinalgError::PatternMismatch)
+        );
+        let mut other = a.to_dence();
+        other[0][0] = 1.;
+        assert_eq!(
+            lu.refactorize(&other.to_sparse()).err(),
+            Some(LinalgError::PatternMismatch)
+        );
+
+        // the default column ordering comes from minimum degree on A^T A
+        let symbolic = LuSymbolic::new(&a, None);
+        let mut q = symbolic.col_perm().to_vec();
+        q.sort();
+        assert_eq!(q, [0, 1, 2, 3, 4]);
+        assert_eq!(symbolic.etree().len(), 5);
+        let lu = SparseLu::factorize(&symbolic, &a, 1.).unwrap();
+        for j in 0..5 {
+            let l = lu.l().col_ptr();
+            let u = lu.u().col_ptr();
+            assert!(l[j + 1] - l[j] <= symbolic.l_counts()[j]);
+            assert!(u[j + 1] - u[j] <= symbolic.u_counts()[j]);
+        }
+
+        let mut coo = CooMatrix::new(3, 3);
+        coo.push(0, 0, 1.);
+        coo.push(1, 1, 1.);
+        assert_eq!(coo.to_csr().lu(None, 1.).err(), Some(LinalgError::Singular));
+
+        let a = poisson(8);
+        let b = Matrix::new_col_vector([1., 0., 0., 2., 0., 0., 0., 3.]);
+        let natural: Vec<_> = (0..8).collect();
+        let chol = a.cholesky(Some(&natural)).unwrap();
+        let l = chol.l().to_dence();
+        assert!(approx_eq(&(&l * l.transpose()), &a.to_dence()));
+        assert!(approx_eq(&(&a.to_dence() * chol.solve(&b)), &b));
+        // a tridiagonal matrix has no fill and a path as elimination tree
+        assert_eq!(chol.symbolic().nnz_l(), 15);
+        assert_eq!(a.cholesky(None).unwrap().symbolic().nnz_l(), 15);
+        assert_eq!(chol.symbolic().etree()[..7], [1, 2, 3, 4, 5, 6, 7]);
+
+        let perm = [0, 7, 1, 6, 2, 5, 3, 4];
+        let symbolic = CholeskySymbolic::new(&a, Some(&perm));
+        let mut chol = SparseCholesky::factorize(&symbolic, &a).unwrap();
+        assert!(approx_eq(&(&a.to_dence() * chol.solve(&b)), &b));
+        let a2 = &a * 3.;
+        chol.refactorize(&a2).unwrap();
+        assert!(approx_eq(&(&a2.to_dence() * chol.solve(&b)), &b));
+        assert_eq!(
+            chol.refactorize(&-&a).err(),
+            Some(LinalgError::NotPositiveDefinite)
+        );
+        assert_eq!(
+            chol.refactorize(&poisson(7)).err(),
+            Some(LinalgError::PatternMismatch)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The ordering must be a permutation of 0..3.")]
+    fn sparse_bad_perm() {
+        CholeskySymbolic::new(&poisson(3), Some(&[0, 2, 0]));
+    }
+
+    #[test]
+    fn ordering() {
+        // 2D five-point Laplacian on a k x k grid, numbered with a scrambled ordering
+        let k = 10;
+        let n = k * k;
+        let scramble: Vec<_> = (0..n).map(|i| (i * 37) % n).collect();
+        let mut coo = CooMatrix::new(n, n);
+        for x in 0..k {
+            for y in 0..k {
+                let i = scramble[x * k + y];
+                coo.push(i, i, 4.);
+                if x + 1 < k {
+                    coo.push(i, scramble[(x + 1) * k + y], -1.);
+                    coo.push(scramble[(x + 1) * k + y], i, -1.);
+                }
+                if y + 1 < k {
+                    coo.push(i, scramble[x * k + y + 1], -1.);
+                    coo.push(scramble[x * k + y + 1], i, -1.);
+                }
+            }
+        }
+        let a = coo.to_csr();
+
+        let p = a.rcm();
+        let mut sorted = p.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+        let b = a.permute(&p, &p);
+        assert_eq!(b.nnz(), a.nnz());
+        assert!(b.bandwidth() <= 2 * k);
+        assert!(b.bandwidth() < a.bandwidth());
+        assert!(b.profile() < a.profile());
+        let (ad, bd) = (a.to_dence(), b.to_dence());
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(bd[i][j], ad[p[i]][p[j]]);
+            }
+        }
+
+        let p = a.amd();
+        let identity: Vec<_> = (0..n).collect();
+        let natural = a.cholesky(Some(&identity)).unwrap();
+        let amd = a.cholesky(Some(&p)).unwrap();
+        assert!(amd.symbolic().nnz_l() < natural.symbolic().nnz_l());
+        // the default ordering is the same minimum degree one
+        let default = a.cholesky(None).unwrap();
+        assert_eq!(default.symbolic().perm(), &p[..]);
+        assert!(default.symbolic().nnz_l() < natural.symbolic().nnz_l());
+        let rhs = Matrix::one(n, 1);
+        assert!(approx_eq(&(&a.to_dence() * amd.solve(&rhs)), &rhs));
+
+        // an arrow matrix fills in completely unless the dense node is eliminated late
+        let mut coo = CooMatrix::new(6, 6);
+        for i in 0..6 {
+            coo.push(i, i, 10.);
+            if i > 0 {
+                coo.push(0, i, 1.);
+                coo.push(i, 0, 1.);
+            }
+        }
+        let arrow = coo.to_csr();
+        assert_eq!(arrow.bandwidth(), 5);
+        assert_eq!(arrow.profile(), 15);
+        let p = arrow.amd();
+        assert_eq!(CholeskySymbolic::new(&arrow, Some(&p)).nnz_l(), 11);
+        assert_eq!(CholeskySymbolic::new(&arrow, None).nnz_l(), 11);
+        let identity: Vec<_> = (0..6).collect();
+        assert_eq!(CholeskySymbolic::new(&arrow, Some(&identity)).nnz_l(), 21);
+    }
+
+    #[test]
+    fn sparse_row_blocks() {
+        // one dense row and many empty ones, so that nnz-balanced blocks are uneven in rows
+        let (m, n) = (300, 40);
+        let mut coo = CooMatrix::new(m, n);
+        for j in 0..n {
+            coo.push(7, j, j as f64 + 1.);
+        }
+        for i in (0..m).step_by(3) {
+            coo.push(i, (i * 13) % n, 0.5 * i as f64 - 20.);
+        }
+        let a = coo.to_csr();
+        let d = a.to_dence();
+        assert_eq!(a.transpose().to_dence(), d.transpose());
+        assert_eq!(a.transpose().transpose(), a);
+
+        let v = Matrix::from_vec_col((0..n).map(|i| (i % 5) as f64).collect());
+        assert!(approx_eq(&(&a * &v), &(&d * &v)));
+        let b = v.concat(&(&v * 2.), Axis::COLUMN);
+        assert!(approx_eq(&(&a * &b), &(&d * &b)));
+    }
+
+    #[test]
+    fn blocked_matmul() {
+        // sizes straddle the register tile and the cache blocks
+        let (m, k, n) = (101, 300, 37);
+        let a = Matrix::from_vec(
+            (0..m * k).map(|i| ((i * 7) % 11) as f64 - 5.).collect(),
+            m,
+            k,
+        );
+        let b = Matrix::from_vec(
+            (0..k * n).map(|i| ((i * 5) % 9) as f64 - 4.).collect(),
+            k,
+            n,
+        );
+        let c = &a * &b;
+        let mut naive = Matrix::zero(m, n);
+        for i in 0..m {
+            for j in 0..n {
+                naive[i][j] = (0..k).map(|p| a[i][p] * b[p][j]).sum();
+            }
+        }
+        assert_eq!(c, naive);
+
+        // the f32 kernel uses a wider tile
+        let a32 = Matrix::from_vec(a.array.iter().map(|&x| x as f32).collect(), m, k);
+        let b32 = Matrix::from_vec(b.array.iter().map(|&x| x as f32).collect(), k, n);
+        let c32 = a32 * b32;
+        assert_eq!(
+            c32.array,
+            naive.array.iter().map(|&x| x as f32).collect::<Vec<_>>()
+        );
+
+        // integers go through the generic kernel, with both tile widths
+        let ai = Matrix::from_vec(a.array.iter().map(|&x| x as i64).collect(), m, k);
+        let bi = Matrix::from_vec(b.array.iter().map(|&x| x as i64).collect(), k, n);
+        assert_eq!(
+            (&ai * &bi).array,
+            naive.array.iter().map(|&x| x as i64).collect::<Vec<_>>()
+        );
+        let ai = Matrix::from_vec(ai.array.iter().map(|&x| x as i32).collect(), m, k);
+        let bi = Matrix::from_vec(bi.array.iter().map(|&x| x as i32).collect(), k, n);
+        assert_eq!(
+            (ai * bi).array,
+            naive.array.iter().map(|&x| x as i32).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn blas() {
+        let a = Matrix::new([[1., 2., 3.], [4., 5., 6.]]);
+        let b = Matrix::new([[1., 0.], [-1., 2.], [0., 1.]]);
+        let c0 = Matrix::new([[1., 1.], [1., 1.]]);
+        let at = a.transpose();
+        let bt = b.transpose();
+
+        for (ta, tb) in [(false, false), (true, false), (false, true), (true, true)] {
+            let x = if ta { &at } else { &a };
+            let y = if tb { &bt } else { &b };
+            let mut c = c0.clone();
+            gemm(2., x, ta, y, tb, 3., &mut c);
+            assert!(approx_eq(&c, &(&(&a * &b) * 2. + &c0 * 3.)));
+        }
+        // beta = 0 discards whatever was in C
+        let mut c = Matrix::new([[f64::NAN, 0.], [0., 0.]]);
+        gemm(1., &a, false, &b, false, 0., &mut c);
+        assert_eq!(c, &a * &b);
+
+        let x = Matrix::new_col_vector([1., -1., 2.]);
+        let mut y = Matrix::new_col_vector([1., 2.]);
+        gemv(1., &a, false, &x, -1., &mut y);
+        assert_eq!(y, Matrix::new_col_vector([4., 9.]));
+        let mut z = Matrix::new([[0., 0., 1.]]);
+        gemv(1., &a, true, &Matrix::new_col_vector([1., 1.]), 2., &mut z);
+        assert_eq!(z, Matrix::new([[5., 7., 11.]]));
+
+        let mut m = Matrix::<f64>::zero(2, 3);
+        ger(2., &Matrix::new_col_vector([1., 2.]), &x, &mut m);
+        assert_eq!(m, Matrix::new([[2., -2., 4.], [4., -4., 8.]]));
+
+        // only the requested triangle is written
+        let s0 = Matrix::new([[1., 7.], [7., 1.]]);
+        let mut s = s0.clone();
+        syrk(1., &a, false, true, 1., &mut s);
+        let full = &a * &at + &s0;
+        assert_eq!(s, Matrix::new([[full[0][0], 7.], [full[1][0], full[1][1]]]));
+        let mut s = Matrix::from_vec(vec![f64::NAN; 9], 3, 3);
+        syrk(0.5, &a, true, false, 0., &mut s);
+        let full = &at * &a * 0.5;
+        for i in 0..3 {
+            for j in 0..3 {
+                if j >= i {
+                    assert_eq!(s[i][j], full[i][j]);
+                } else {
+                    assert!(s[i][j].is_nan());
+                }
+            }
+        }
+
+        // several diagonal blocks
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let b = Matrix::<f64>::randn(230, 40, &mut rng);
+        let c0 = Matrix::<f64>::randn(230, 230, &mut rng);
+        let full = &(&b * &b.transpose()) * 2. + &c0 * 3.;
+        for lower in [true, false] {
+            let mut c = c0.clone();
+            syrk(2., &b, false, lower, 3., &mut c);
+            for i in 0..230 {
+                for j in 0..230 {
+                    let expected = if (j <= i) == lower || i == j {
+                        full[i][j]
+                    } else {
+                        c0[i][j]
+                    };
+                    assert!((c[i][j] - expected).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is empty")]
+    fn view_empty() {
+        let a = Matrix::new([[1., 2.], [3., 4.]]);
+        a.slice(1..1, ..);
+    }
+
+    #[test]
+    fn view() {
+        let a = Matrix::new([[1., 2., 3., 4.], [5., 6., 7., 8.], [9., 10., 11., 12.]]);
+        let s = a.slice(1..3, 1..);
+        assert_eq!(s.shape(), (2, 3));
+        assert_eq!(s.to_matrix(), Matrix::new([[6., 7., 8.], [10., 11., 12.]]));
+        assert_eq!(s.t().to_matrix(), s.to_matrix().transpose());
+        assert_eq!(s.t().get(2, 1), Some(12.));
+        assert_eq!(s.get(2, 0), None);
+        assert_eq!(a.row(1).to_matrix(), Matrix::new([[5., 6., 7., 8.]]));
+        assert_eq!(a.col(2).to_matrix(), Matrix::new_col_vector([3., 7., 11.]));
+        assert_eq!(
+            a.t().slice(1..2, ..).to_matrix(),
+            Matrix::new([[2., 6., 10.]])
+        );
+
+        assert_eq!(s.sum(None), Matrix::new([[54.]]));
+        assert_eq!(s.sum(Some(Axis::ROW)), Matrix::new_col_vector([21., 33.]));
+        assert_eq!(s.sum(Some(Axis::COLUMN)), Matrix::new([[16., 18., 20.]]));
+        assert_eq!(
+            a.t().max(Some(Axis::ROW)),
+            a.max(Some(Axis::COLUMN)).transpose()
+        );
+        assert_eq!(s.min(None), Matrix::new([[6.]]));
+
+        let b = Matrix::new([[1., 1., 1.], [2., 2., 2.]]);
+        assert_eq!(s + &b, Matrix::new([[7., 8., 9.], [12., 13., 14.]]));
+        assert_eq!(&b - s, Matrix::new([[-5., -6., -7.], [-8., -9., -10.]]));
+        assert_eq!(s.t() * &b, s.to_matrix().transpose() * &b);
+        assert_eq!(
+            a.slice(.., 1..3) * s,
+            a.slice(.., 1..3).to_matrix() * s.to_matrix()
+        );
+        assert_eq!(a.t() * a.view(), a.transpose() * &a);
+
+        let mut c = a.clone();
+        c.slice_mut(0..2, 2..4).fill(0.);
+        c.col_mut(0).assign(a.slice(.., 3..4));
+        {
+            let mut r = c.row_mut(2);
+            r += &Matrix::new([[1., 1., 1., 1.]]);
+        }
+        let mut t = c.view_mut().t();
+        *t.get_mut(3, 0).unwrap() = -1.;
+        assert_eq!(
+            c,
+            Matrix::new([[4., 2., 0., -1.], [8., 6., 0., 0.], [13., 11., 12., 13.]])
+        );
+    }
+
+    #[test]
+    fn try_ops() {
+        assert_eq!(
+            Matrix::try_from_vec(vec![1., 2., 3.], 2, 2),
+            Err(MatrixError::LengthMismatch {
+                len: 3,
+                rows: 2,
+                cols: 2
+            })
+        );
+        assert_eq!(
+            Matrix::<f64>::try_from_vec(vec![], 0, 3),
+            Err(MatrixError::ZeroDimension { rows: 0, cols: 3 })
+        );
+        let mut a = Matrix::try_from_vec(vec![1., 2., 3., 4., 5., 6.], 2, 3).unwrap();
+        assert_eq!(
+            a.try_reshape(4, 2),
+            Err(MatrixError::ReshapeMismatch {
+                from: (2, 3),
+                to: (4, 2)
+            })
+        );
+        assert_eq!(a.try_reshape(3, 2), Ok(()));
+        assert_eq!(a.shape(), (3, 2));
+
+        let b = Matrix::new([[1., 1.], [2., 2.]]);
+        let err = MatrixError::ShapeMismatch {
+            op: "concat",
+            lhs: (3, 2),
+            rhs: (2, 2),
+        };
+        assert_eq!(a.try_concat(&b, Axis::COLUMN), Err(err));
+        assert_eq!(a.try_concat(&b, Axis::ROW).unwrap().shape(), (5, 2));
+        assert!(a.try_add(&b).is_err());
+        assert!(a.try_sub(&b).is_err());
+        assert!(a.try_cwise_mul(&b).is_err());
+        assert!(a.try_cwise_div(&b).is_err());
+        assert_eq!(b.try_cwise_mul(&b), Ok(b.cwise_mul(&b)));
+        assert_eq!(a.try_matmul(&b), Ok(&a * &b));
+        assert_eq!(
+            b.try_matmul(&a),
+            Err(MatrixError::ShapeMismatch {
+                op: "matmul",
+                lhs: (2, 2),
+                rhs: (3, 2)
+            })
+        );
+        assert!(Matrix::new([[2.]]).try_matmul(&a).is_ok());
+        assert_eq!(b.try_diag(), Err(MatrixError::NotVector { shape: (2, 2) }));
+        assert_eq!(b.as_scalar(), None);
+        assert_eq!(
+            b.try_as_scalar(),
+            Err(MatrixError::NotScalar { shape: (2, 2) })
+        );
+        assert_eq!(
+            err.to_string(),
+            "Shapes (3, 2) and (2, 2) are incompatible for concat."
+        );
+
+        let huge = usize::MAX / 2 + 1;
+        assert_eq!(
+            Matrix::try_from_vec(vec![0.; 4], huge, 2),
+            Err(MatrixError::SizeOverflow {
+                rows: huge,
+                cols: 2
+            })
+        );
+        assert_eq!(
+            a.try_reshape(huge, 2),
+            Err(MatrixError::SizeOverflow {
+                rows: huge,
+                cols: 2
+            })
+        );
+        assert_eq!(
+            Matrix::<f64>::try_from_vec_row(vec![]),
+            Err(MatrixError::ZeroDimension { rows: 1, cols: 0 })
+        );
+        assert_eq!(
+            Matrix::try_from_vec_col(vec![1., 2.]),
+            Ok(Matrix::new([[1.], [2.]]))
+        );
+
+        let mut c = b.clone();
+        assert_eq!(
+            c.try_add_assign(&a),
+            Err(MatrixError::ShapeMismatch {
+                op: "add_assign",
+                lhs: (2, 2),
+                rhs: (3, 2)
+            })
+        );
+        assert!(c.try_sub_assign(&Matrix::new([[1., 2.]])).is_ok());
+        assert_eq!(c, Matrix::new([[0., -1.], [1., 0.]]));
+        assert!(c.try_add_assign(&b).is_ok());
+        assert_eq!(c, Matrix::new([[1., 0.], [3., 2.]]));
+
+        let not_square = MatrixError::NotSquare { shape: (3, 2) };
+        assert_eq!(a.try_trace(), Err(not_square));
+        assert_eq!(b.try_trace(), Ok(3.));
+        assert_eq!(a.try_det(), Err(not_square));
+        assert!(a.try_lu().is_err());
+        assert!(a.try_ldlt().is_err());
+        assert!(a.try_hessenberg().is_err());
+        assert_eq!(a.inverse(), Err(LinalgError::Shape(not_square)));
+        assert_eq!(a.eig().unwrap_err(), LinalgError::Shape(not_square));
+        assert_eq!(a.cholesky().unwrap_err(), LinalgError::Shape(not_square));
+        let spd = Matrix::new([[4., 1.], [1., 3.]]);
+        let rhs = Matrix::new([[1.], [2.], [3.]]);
+        let mismatch = MatrixError::ShapeMismatch {
+            op: "solve",
+            lhs: (2, 2),
+            rhs: (3, 1),
+        };
+        assert_eq!(spd.solve(&rhs), Err(LinalgError::Shape(mismatch)));
+        assert_eq!(spd.ldlt().solve(&rhs), Err(LinalgError::Shape(mismatch)));
+        assert_eq!(spd.cholesky().unwrap().try_solve(&rhs), Err(mismatch));
+        assert_eq!(spd.try_lstsq(&rhs), Err(mismatch));
+        assert_eq!(
+            LinalgError::Shape(not_square).to_string(),
+            "Expected a square matrix, got (3, 2)."
+        );
+    }
+
+    #[test]
+    fn broadcast() {
+        let a = Matrix::new([[1., 2., 3.], [4., 5., 6.]]);
+        let row = Matrix::new([[10., 20., 30.]]);
+        let col = Matrix::new_col_vector([1., -1.]);
+        let s = Matrix::new([[2.]]);
+
+        assert_eq!(&a + &row, Matrix::new([[11., 22., 33.], [14., 25., 36.]]));
+        assert_eq!(&row + &a, &a + &row);
+        assert_eq!(&a - &col, Matrix::new([[0., 1., 2.], [5., 6., 7.]]));
+        assert_eq!(&a - &s, Matrix::new([[-1., 0., 1.], [2., 3., 4.]]));
+        assert_eq!(
+            a.cwise_mul(&col),
+            Matrix::new([[1., 2., 3.], [-4., -5., -6.]])
+        );
+        assert_eq!(
+            a.cwise_div(&s),
+            Matrix::new([[0.5, 1., 1.5], [2., 2.5, 3.]])
+        );
+        // (m, 1) against (1, n) expands both sides
+        assert_eq!(&col + &row, Matrix::new([[11., 21., 31.], [9., 19., 29.]]));
+
+        let mut b = a.clone();
+        b += &row;
+        b -= &col;
+        assert_eq!(b, Matrix::new([[10., 21., 32.], [15., 26., 37.]]));
+
+        let bad = Matrix::new([[1., 2.]]);
+        assert_eq!(
+            a.try_add(&bad),
+            Err(MatrixError::ShapeMismatch {
+                op: "add",
+                lhs: (2, 3),
+                rhs: (1, 2)
+            })
+        );
+        assert!(a.try_cwise_mul(&row).is_ok());
+        assert!(a.try_sub(&Matrix::new_col_vector([1., 2., 3.])).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Shapes (1, 3) and (2, 3) are incompatible for add_assign.")]
+    fn broadcast_assign_grow() {
+        let mut row = Matrix::new([[10., 20., 30.]]);
+        row += &Matrix::new([[1., 2., 3.], [4., 5., 6.]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Axis 3 is out of range for 3 dimensions.")]
+    fn tensor_transpose_bad_axis() {
+        Tensor::<f64, 3>::zeros([2, 3, 4]).transpose_axes(0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot reduce an empty axis.")]
+    fn tensor_max_empty_axis() {
+        Tensor::<f64, 3>::zeros([3, 0, 2]).max(Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn tensor_size_overflow() {
+        Tensor::<f64, 3>::from_vec(vec![0.; 4], [usize::MAX / 2 + 1, 4, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rows and columns cannot be set to zero")]
+    fn tensor_empty_to_matrix() {
+        let _ = Matrix::from(Tensor::<f64, 2>::zeros([0, 3]));
+    }
+
+    #[test]
+    fn tensor() {
+        let t = Tensor::from_vec((0..24).map(|x| x as f64).collect(), [2, 3, 4]);
+        assert_eq!(t.shape(), [2, 3, 4]);
+        assert_eq!(t[[1, 2, 3]], 23.);
+        assert_eq!(t.get([1, 0, 2]), Some(14.));
+        assert_eq!(t.get([2, 0, 0]), None);
+
+        let mut z = Tensor::<f64, 3>::zeros([2, 3, 4]);
+        z[[0, 1, 2]] = 5.;
+        assert_eq!(z.sum(None).to_slice(), &[5.]);
+
+        // axis k of the permuted tensor is axis axes[k] of the original
+        let p = t.permute([2, 0, 1]);
+        assert_eq!(p.shape(), [4, 2, 3]);
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    assert_eq!(p[[k, i, j]], t[[i, j, k]]);
+                }
+            }
+        }
+        assert_eq!(p.permute([1, 2, 0]), t);
+        let s = t.transpose_axes(0, 2);
+        assert_eq!(s.shape(), [4, 3, 2]);
+        assert_eq!(s[[3, 1, 0]], t[[0, 1, 3]]);
+
+        assert_eq!(t.sum(Some(1)).shape(), [2, 1, 4]);
+        assert_eq!(t.sum(Some(1))[[1, 0, 2]], 14. + 18. + 22.);
+        assert_eq!(t.max(Some(2)).to_slice(), &[3., 7., 11., 15., 19., 23.]);
+        assert_eq!(t.min(Some(0)).to_slice(), &t.to_slice()[..12]);
+        assert_eq!(t.sum(None).to_slice(), &[276.]);
+        // a trailing empty axis gives an empty result rather than a panic
+        let e = Tensor::<f64, 3>::zeros([3, 2, 0]).sum(Some(0));
+        assert_eq!(e.shape(), [1, 2, 0]);
+        assert!(e.to_slice().is_empty());
+        // summing over an empty axis gives zeros
+        let e = Tensor::<f64, 3>::zeros([3, 0, 2]);
+        assert_eq!(e.sum(Some(1)), Tensor::zeros([3, 1, 2]));
+        assert_eq!(e.sum(None).to_slice(), &[0.]);
+
+        let u = &t + &t;
+        assert_eq!(&u - &t, t);
+        assert_eq!(&t * 2., u);
+        assert_eq!((&u / &(&t + 1.))[[0, 0, 1]], 1.);
+        assert_eq!((-&t)[[0, 1, 0]], -4.);
+
+        let r = t.clone().reshape([6, 4]);
+        assert_eq!(r[[5, 3]], 23.);
+        let m: Matrix<f64> = r.clone().into();
+        assert_eq!(m.shape(), (6, 4));
+        assert_eq!(m.get(5, 3), Some(23.));
+        assert_eq!(Tensor::from(m), r);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let x = Tensor::<f64, 4>::randu([2, 2, 3, 1], &mut rng);
+        assert_eq!(x.len(), 12);
+        assert!(x.to_slice().iter().all(|&v| (0. ..1.).contains(&v)));
+    }
+
+    #[test]
+    fn einsum_contractions() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let close = |x: &[f64], y: &[f64]| {
+            x.len() == y.len() && x.iter().zip(y.iter()).all(|(a, b)| (a - b).abs() < 1e-10)
+        };
+
+        let a = Matrix::<f64>::randn(4, 3, &mut rng);
+        let b = Matrix::<f64>::randn(3, 5, &mut rng);
+        let c: Tensor<f64, 2> = einsum("ij,jk->ik", &[&a, &b]).unwrap();
+        assert!(close(c.to_slice(), (&a * &b).to_slice()));
+        let ct: Tensor<f64, 2> = einsum("ij,jk->ki", &[&a, &b]).unwrap();
+        assert_eq!(ct, c.permute([1, 0]));
+        // implicit output: labels used once, sorted
+        let c2: Tensor<f64, 2> = einsum("ij,jk", &[&a, &b]).unwrap();
+        assert_eq!(c2, c);
+
+        let s = Matrix::<f64>::randn(4, 4, &mut rng);
+        let tr: Tensor<f64, 0> = einsum("ii->", &[&s]).unwrap();
+        assert!((tr[[]] - (0..4).map(|i| s[i][i]).sum::<f64>()).abs() < 1e-12);
+        let d: Tensor<f64, 1> = einsum("ii->i", &[&s]).unwrap();
+        assert_eq!(d[[2]], s[2][2]);
+        let total: Tensor<f64, 0> = einsum("ij->", &[&a]).unwrap();
+        assert!((total[[]] - a.to_slice().iter().sum::<f64>()).abs() < 1e-12);
+
+        let x = Tensor::<f64, 1>::randn([3], &mut rng);
+        let y = Tensor::<f64, 1>::randn([2], &mut rng);
+        let o: Tensor<f64, 2> = einsum("i,j->ij", &[&x, &y]).unwrap();
+        assert_eq!(o.shape(), [3, 2]);
+        assert!((o[[2, 1]] - x[[2]] * y[[1]]).abs() < 1e-15);
+
+        let p = Tensor::<f64, 3>::randn([2, 4, 3], &mut rng);
+        let q = Tensor::<f64, 3>::randn([2, 3, 5], &mut rng);
+        let r: Tensor<f64, 3> = einsum("bij,bjk->bik", &[&p, &q]).unwrap();
+        assert_eq!(r.shape(), [2, 4, 5]);
+        let mut expected = 0.;
+        for j in 0..3 {
+            expected += p[[1, 2, j]] * q[[1, j, 4]];
+        }
+        assert!((r[[1, 2, 4]] - expected).abs() < 1e-12);
+
+        // a small tensor network: A_ij B_jkl C_lk v_i -> scalar
+        let t = Tensor::<f64, 3>::randn([3, 2, 5], &mut rng);
+        let u = Matrix::<f64>::randn(5, 2, &mut rng);
+        let v = Tensor::<f64, 1>::randn([4], &mut rng);
+        let net: Tensor<f64, 0> = einsum("ij,jkl,lk,i->", &[&a, &t, &u, &v]).unwrap();
+        let mut expected = 0.;
+        for i in 0..4 {
+            for j in 0..3 {
+                for k in 0..2 {
+                    for l in 0..5 {
+                        expected += a[i][j] * t[[j, k, l]] * u[l][k] * v[[i]];
+                    }
+                }
+            }
+        }
+        assert!((net[[]] - expected).abs() < 1e-10);
+
+        let operands: [&dyn EinsumOperand<f64>; 2] = [&a, &a];
+        assert_eq!(
+            einsum::<f64, 2>("ij,jk->ik", &operands),
+            Err(EinsumError::DimensionMismatch {
+                index: 'j',
+                first: 3,
+                second: 4
+            })
+        );
+        assert_eq!(
+            einsum::<f64, 2>("ij->iz", &[&a]),
+            Err(EinsumError::UnknownOutputIndex('z'))
+        );
+        assert_eq!(
+            einsum::<f64, 1>("ij->ij", &[&a]),
+            Err(EinsumError::OutputRank {
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            einsum::<f64, 2>("ijk->ij", &[&a]),
+            Err(EinsumError::RankMismatch {
+                operand: 0,
+                subscripts: 3,
+                rank: 2
+            })
+        );
+    }
+
+    #[test]
+    fn batched_matmul() {
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let a = Tensor::<f64, 3>::randn([5, 3, 4], &mut rng);
+        let b = Tensor::<f64, 3>::randn([5, 4, 2], &mut rng);
+        let c = a.batched_matmul(&b);
+        assert_eq!(c.shape(), [5, 3, 2]);
+
+        let mats: Vec<Matrix<f64>> = a.clone().try_into().unwrap();
+        let rhs: Vec<Matrix<f64>> = b.clone().try_into().unwrap();
+        assert_eq!(mats.len(), 5);
+        assert_eq!(mats[2].shape(), (3, 4));
+        assert_eq!(mats[2].get(1, 3), a.get([2, 1, 3]));
+        let expected: Vec<Matrix<f64>> = mats.iter().zip(rhs.iter()).map(|(x, y)| x * y).collect();
+        let expected = Tensor::try_from(expected).unwrap();
+        assert!((&c - &expected).to_slice().iter().all(|x| x.abs() < 1e-12));
+        assert_eq!(Tensor::try_from(mats.clone()), Ok(a.clone()));
+
+        // both directions reject what a Matrix cannot hold
+        for dims in [[2, 3, 0], [0, 3, 0]] {
+            assert_eq!(
+                Vec::<Matrix<f64>>::try_from(Tensor::zeros(dims)),
+                Err(MatrixError::ZeroDimension { rows: 3, cols: 0 })
+            );
+        }
+        assert_eq!(
+            Tensor::<f64, 3>::try_from(Vec::new()),
+            Err(MatrixError::EmptyStack)
+        );
+        assert_eq!(
+            Tensor::try_from(vec![mats[0].clone(), rhs[0].clone()]),
+            Err(MatrixError::ShapeMismatch {
+                op: "stack",
+                lhs: (3, 4),
+                rhs: (4, 2)
+            })
+        );
+
+        // a batch of one broadcasts on either side
+        let w = Tensor::<f64, 3>::randn([1, 4, 2], &mut rng);
+        let wide = Tensor::try_from(vec![Matrix::from(w.clone().reshape([4, 2])); 5]).unwrap();
+        assert_eq!(a.batched_matmul(&w), a.batched_matmul(&wide));
+        let v = Tensor::<f64, 3>::randn([1, 2, 3], &mut rng);
+        let out: Vec<Matrix<f64>> = v.batched_matmul(&a).try_into().unwrap();
+        assert_eq!(out.len(), 5);
+        let v: Matrix<f64> = v.reshape([2, 3]).into();
+        let diff = &out[4] - &(&v * &mats[4]);
+        assert!(diff.to_slice().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    #[should_panic]
+    fn batched_matmul_mismatch() {
+        let a = Tensor::<f64, 3>::zeros([2, 3, 4]);
+        let b = Tensor::<f64, 3>::zeros([3, 4, 2]);
+        a.batched_matmul(&b);
+    }
+
+    #[test]
+    fn rand_generic() {
+        use rand::{rngs::StdRng, RngCore};
+        use rand_distr::{Bernoulli, Exp};
+
+        let mut std = StdRng::seed_from_u64(5);
+        let a = Matrix::<f64>::randu(3, 4, &mut std);
+        assert_eq!(a.shape(), (3, 4));
+        assert!(a.to_slice().iter().all(|&v| (0. ..1.).contains(&v)));
+        assert_eq!(a, Matrix::randu(3, 4, &mut StdRng::seed_from_u64(5)));
+
+        // trait objects and the thread-local generator work too
+        let mut chacha = ChaCha8Rng::seed_from_u64(5);
+        let dynrng: &mut dyn RngCore = &mut chacha;
+        let n = Matrix::<f32>::randn(2, 2, dynrng);
+        assert_eq!(n, Matrix::randn(2, 2, &mut ChaCha8Rng::seed_from_u64(5)));
+        let _ = Matrix::<u8>::rand(2, 3, &mut rand::thread_rng());
+
+        let coin = Matrix::<bool>::rand_with(Bernoulli::new(0.5).unwrap(), 8, 8, &mut std);
+        assert_eq!(coin.shape(), (8, 8));
+        let e = Matrix::<f64>::rand_with(Exp::new(2.).unwrap(), 10, 10, &mut std);
+        assert!(e.to_slice().iter().all(|&v| v >= 0.));
+    }
+
+    #[test]
+    fn rand_blocks() {
+        // large enough to span several sampling blocks
+        let a = Matrix::<f64>::par_randn(100, 100, &mut ChaCha8Rng::seed_from_u64(6));
+        let b = Matrix::<f64>::par_randn(100, 100, &mut ChaCha8Rng::seed_from_u64(6));
+        assert_eq!(a, b);
+        assert_ne!(a.to_slice()[..16], a.to_slice()[4096..4112]);
+        let mean = a.to_slice().iter().sum::<f64>() / 1e4;
+        assert!(mean.abs() < 0.05);
+
+        // the serial generators keep the plain per-entry sequence of the engine
+        let normal = rand_distr::Normal::new(0., 1.).unwrap();
+        assert_eq!(
+            Matrix::<f64>::randn(5, 3, &mut ChaCha8Rng::seed_from_u64(6)),
+            Matrix::rand_with(normal, 5, 3, &mut ChaCha8Rng::seed_from_u64(6))
+        );
+
+        // consecutive draws from one engine differ
+        let mut rng = ChaCha8Rng::seed_from_u64(6);
+        let u = Matrix::<f32>::par_randu(70, 70, &mut rng);
+        let v = Matrix::<f32>::par_randu(70, 70, &mut rng);
+        assert_ne!(u, v);
+
+        #[cfg(feature = "rayon")]
+        for threads in [1, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let c = pool.install(|| Matrix::par_randn(100, 100, &mut ChaCha8Rng::seed_from_u64(6)));
+            assert_eq!(a, c);
+            let t = pool.install(|| {
+                Tensor::<f32, 2>::par_randu([70, 70], &mut ChaCha8Rng::seed_from_u64(6))
+            });
+            assert_eq!(t.to_slice(), u.to_slice());
+        }
     }
 }
@@ -0,0 +1,56 @@
+pub mod cholesky;
+pub mod eigen;
+pub mod lu;
+pub mod qr;
+pub mod schur;
+pub mod svd;
+
+use crate::{error::MatrixError, Matrix};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinalgError {
+    Singular,
+    NotPositiveDefinite,
+    NoConvergence,
+    PatternMismatch,
+    Shape(MatrixError),
+}
+
+impl std::fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinalgError::Singular => write!(f, "The matrix is singular."),
+            LinalgError::NotPositiveDefinite => {
+                write!(f, "The matrix is not positive definite.")
+            }
+            LinalgError::NoConvergence => write!(f, "The iteration did not converge."),
+            LinalgError::PatternMismatch => {
+                write!(
+                    f,
+                    "The sparsity pattern differs from the symbolic analysis."
+                )
+            }
+            LinalgError::Shape(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
+
+// the right-hand side of a solve needs as many rows as the factored matrix
+pub(crate) fn check_rhs<T>(lhs: (usize, usize), b: &Matrix<T>) -> Result<(), MatrixError> {
+    if lhs.0 != b.rows {
+        return Err(MatrixError::ShapeMismatch {
+            op: "solve",
+            lhs,
+            rhs: b.shape(),
+        });
+    }
+    Ok(())
+}
+
+impl From<MatrixError> for LinalgError {
+    fn from(e: MatrixError) -> Self {
+        LinalgError::Shape(e)
+    }
+}
@@ -0,0 +1,381 @@
+use crate::{
+    error::MatrixError,
+    linalg::{check_rhs, LinalgError},
+    Matrix,
+};
+use num_traits::Float;
+
+// A = LL^T. Only the lower triangle of A is read.
+#[derive(Clone, Debug)]
+pub struct Cholesky<T> {
+    l: Matrix<T>,
+}
+
+impl<T> Cholesky<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Result<Self, LinalgError> {
+        mat.check_square()?;
+        let n = mat.rows;
+        let mut l = Matrix::zero(n, n);
+
+        for j in 0..n {
+            let d = (0..j).fold(mat.array[j * n + j], |s, k| {
+                s - l.array[j * n + k] * l.array[j * n + k]
+            });
+            if d <= T::zero() || d.is_nan() {
+                return Err(LinalgError::NotPositiveDefinite);
+            }
+            let d = d.sqrt();
+            l.array[j * n + j] = d;
+            for i in (j + 1)..n {
+                let s = (0..j).fold(mat.array[i * n + j], |s, k| {
+                    s - l.array[i * n + k] * l.array[j * n + k]
+                });
+                l.array[i * n + j] = s / d;
+            }
+        }
+        Ok(Self { l })
+    }
+
+    pub fn l(&self) -> Matrix<T> {
+        self.l.clone()
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Matrix<T> {
+        self.try_solve(b).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_solve(&self, b: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        check_rhs(self.l.shape(), b)?;
+        let n = self.l.rows;
+        let k = b.cols;
+        let mut x = b.clone();
+
+        // L y = b
+        for i in 0..n {
+            for j in 0..i {
+                let l = self.l.array[i * n + j];
+                for c in 0..k {
+                    x.array[i * k + c] = x.array[i * k + c] - l * x.array[j * k + c];
+                }
+            }
+            let d = self.l.array[i * n + i];
+            for c in 0..k {
+                x.array[i * k + c] = x.array[i * k + c] / d;
+            }
+        }
+        // L^T x = y
+        for i in (0..n).rev() {
+            for j in (i + 1)..n {
+                let l = self.l.array[j * n + i];
+                for c in 0..k {
+                    x.array[i * k + c] = x.array[i * k + c] - l * x.array[j * k + c];
+                }
+            }
+            let d = self.l.array[i * n + i];
+            for c in 0..k {
+                x.array[i * k + c] = x.array[i * k + c] / d;
+            }
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Matrix<T> {
+        self.solve(&Matrix::eye(self.l.rows))
+    }
+
+    pub fn det(&self) -> T {
+        let n = self.l.rows;
+        (0..n)
+            .fold(T::one(), |d, i| d * self.l.array[i * n + i])
+            .powi(2)
+    }
+
+    pub fn log_det(&self) -> T {
+        let n = self.l.rows;
+        (0..n).fold(T::zero(), |s, i| s + self.l.array[i * n + i].ln()) * T::from(2).unwrap()
+    }
+}
+
+// PAP^T = LDL^T with Bunch-Kaufman pivoting. D has 1x1 and 2x2 diagonal blocks,
+// so semi-definite and indefinite matrices are handled as well.
+#[derive(Clone, Debug)]
+pub struct Ldlt<T> {
+    ldl: Matrix<T>,
+    perm: Vec<usize>,
+    blocks: Vec<usize>,
+    singular: bool,
+}
+
+impl<T> Ldlt<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Self {
+        Self::try_new(mat).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(mat: &Matrix<T>) -> Result<Self, MatrixError> {
+        mat.check_square()?;
+        let n = mat.rows;
+        let mut a = mat.clone();
+        // the whole trailing block is kept symmetric from the lower triangle
+        for i in 0..n {
+            for j in (i + 1)..n {
+                a.array[i * n + j] = a.array[j * n + i];
+            }
+        }
+        let mut perm: Vec<_> = (0..n).collect();
+        let mut blocks = Vec::new();
+        let mut singular = false;
+        let scale = a.array.iter().fold(T::zero(), |m, x| m.max(x.abs()));
+        let tol = scale * T::epsilon() * T::from(n).unwrap();
+        let alpha = (T::one() + T::from(17).unwrap().sqrt()) / T::from(8).unwrap();
+
+        let mut k = 0;
+        while k < n {
+            let absakk = a.array[k * n + k].abs();
+            let (r, colmax) = ((k + 1)..n).fold((k, T::zero()), |(r, m), i| {
+                let v = a.array[i * n + k].abs();
+                if v > m {
+                    (i, v)
+                } else {
+                    (r, m)
+                }
+            });
+
+            let (kp, kstep) = if absakk.max(colmax).is_zero() || absakk >= alpha * colmax {
+                (k, 1)
+            } else {
+                let rowmax = (k..n)
+                    .filter(|&j| j != r)
+                    .fold(T::zero(), |m, j| m.max(a.array[r * n + j].abs()));
+                if absakk * rowmax >= alpha * colmax * colmax {
+                    (k, 1)
+                } else if a.array[r * n + r].abs() >= alpha * rowmax {
+                    (r, 1)
+                } else {
+                    (r, 2)
+                }
+            };
+
+            let kk = k + kstep - 1;
+            if kp != kk {
+                for j in 0..n {
+                    a.array.swap(kk * n + j, kp * n + j);
+                }
+                for i in 0..n {
+                    a.array.swap(i * n + kk, i * n + kp);
+                }
+                perm.swap(kk, kp);
+            }
+
+            if kstep == 1 {
+                let mut d = a.array[k * n + k];
+                // a pivot at rounding level is what is left of an exactly singular
+                // semi-definite input; drop it instead of dividing by it
+                if d.abs() <= tol {
+                    d = T::zero();
+                    a.array[k * n + k] = d;
+                    singular = true;
+                }
+                let w: Vec<_> = ((k + 1)..n).map(|i| a.array[i * n + k]).collect();
+                for i in (k + 1)..n {
+                    let l = if d.is_zero() {
+                        T::zero()
+                    } else {
+                        w[i - k - 1] / d
+                    };
+                    for j in (k + 1)..n {
+                        a.array[i * n + j] = a.array[i * n + j] - l * w[j - k - 1];
+                    }
+                    a.array[i * n + k] = l;
+                }
+                for i in (k + 1)..n {
+                    a.array[k * n + i] = T::zero();
+                }
+            } else {
+                let (d11, d21, d22) = (
+                    a.array[k * n + k],
+                    a.array[(k + 1) * n + k],
+                    a.array[(k + 1) * n + k + 1],
+                );
+                let det = d11 * d22 - d21 * d21;
+                if det.abs() <= tol * d11.abs().max(d21.abs()).max(d22.abs()) {
+                    singular = true;
+                }
+                let mut w = Vec::with_capacity(2 * (n - k - 2));
+                for i in (k + 2)..n {
+                    w.push(a.array[i * n + k]);
+                    w.push(a.array[i * n + k + 1]);
+                }
+                for i in (k + 2)..n {
+                    let (w1, w2) = (w[2 * (i - k - 2)], w[2 * (i - k - 2) + 1]);
+                    let l1 = (w1 * d22 - w2 * d21) / det;
+                    let l2 = (w2 * d11 - w1 * d21) / det;
+                    for j in (k + 2)..n {
+                        let (v1, v2) = (w[2 * (j - k - 2)], w[2 * (j - k - 2) + 1]);
+                        a.array[i * n + j] = a.array[i * n + j] - l1 * v1 - l2 * v2;
+                    }
+                    a.array[i * n + k] = l1;
+                    a.array[i * n + k + 1] = l2;
+                }
+                for i in (k + 2)..n {
+                    a.array[k * n + i] = T::zero();
+                    a.array[(k + 1) * n + i] = T::zero();
+                }
+            }
+            blocks.push(kstep);
+            k += kstep;
+        }
+
+        Ok(Self {
+            ldl: a,
+            perm,
+            blocks,
+            singular,
+        })
+    }
+
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn p(&self) -> Matrix<T> {
+        let n = self.ldl.rows;
+        let mut p = Matrix::zero(n, n);
+        for (i, &j) in self.perm.iter().enumerate() {
+            p.array[i * n + j] = T::one();
+        }
+        p
+    }
+
+    pub fn l(&self) -> Matrix<T> {
+        let n = self.ldl.rows;
+        let mut l = Matrix::eye(n);
+        let mut k = 0;
+        for &s in self.blocks.iter() {
+            for j in k..(k + s) {
+                for i in (k + s)..n {
+                    l.array[i * n + j] = self.ldl.array[i * n + j];
+                }
+            }
+            k += s;
+        }
+        l
+    }
+
+    pub fn d(&self) -> Matrix<T> {
+        let n = self.ldl.rows;
+        let mut d = Matrix::zero(n, n);
+        let mut k = 0;
+        for &s in self.blocks.iter() {
+            for i in k..(k + s) {
+                for j in k..(k + s) {
+                    d.array[i * n + j] = self.ldl.array[i.max(j) * n + i.min(j)];
+                }
+            }
+            k += s;
+        }
+        d
+    }
+
+    pub fn det(&self) -> T {
+        let n = self.ldl.rows;
+        let mut k = 0;
+        let mut det = T::one();
+        for &s in self.blocks.iter() {
+            let a = &self.ldl.array;
+            det = det
+                * if s == 1 {
+                    a[k * n + k]
+                } else {
+                    a[k * n + k] * a[(k + 1) * n + k + 1] - a[(k + 1) * n + k] * a[(k + 1) * n + k]
+                };
+            k += s;
+        }
+        det
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        check_rhs(self.ldl.shape(), b)?;
+        if self.singular {
+            return Err(LinalgError::Singular);
+        }
+        let n = self.ldl.rows;
+        let k = b.cols;
+        let a = &self.ldl.array;
+
+        let mut x = Matrix::zero(n, k);
+        for (i, &p) in self.perm.iter().enumerate() {
+            x.array[i * k..(i + 1) * k].copy_from_slice(&b.array[p * k..(p + 1) * k]);
+        }
+
+        let l = self.l();
+        for i in 0..n {
+            for j in 0..i {
+                let lij = l.array[i * n + j];
+                for c in 0..k {
+                    x.array[i * k + c] = x.array[i * k + c] - lij * x.array[j * k + c];
+                }
+            }
+        }
+
+        let mut s = 0;
+        for &size in self.blocks.iter() {
+            if size == 1 {
+                let d = a[s * n + s];
+                for c in 0..k {
+                    x.array[s * k + c] = x.array[s * k + c] / d;
+                }
+            } else {
+                let (d11, d21, d22) = (a[s * n + s], a[(s + 1) * n + s], a[(s + 1) * n + s + 1]);
+                let det = d11 * d22 - d21 * d21;
+                for c in 0..k {
+                    let (y1, y2) = (x.array[s * k + c], x.array[(s + 1) * k + c]);
+                    x.array[s * k + c] = (d22 * y1 - d21 * y2) / det;
+                    x.array[(s + 1) * k + c] = (d11 * y2 - d21 * y1) / det;
+                }
+            }
+            s += size;
+        }
+
+        for i in (0..n).rev() {
+            for j in (i + 1)..n {
+                let lji = l.array[j * n + i];
+                for c in 0..k {
+                    x.array[i * k + c] = x.array[i * k + c] - lji * x.array[j * k + c];
+                }
+            }
+        }
+
+        let mut ret = Matrix::zero(n, k);
+        for (i, &p) in self.perm.iter().enumerate() {
+            ret.array[p * k..(p + 1) * k].copy_from_slice(&x.array[i * k..(i + 1) * k]);
+        }
+        Ok(ret)
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn cholesky(&self) -> Result<Cholesky<T>, LinalgError> {
+        Cholesky::new(self)
+    }
+
+    pub fn ldlt(&self) -> Ldlt<T> {
+        Ldlt::new(self)
+    }
+
+    pub fn try_ldlt(&self) -> Result<Ldlt<T>, MatrixError> {
+        Ldlt::try_new(self)
+    }
+}
@@ -0,0 +1,509 @@
+use crate::{
+    linalg::{
+        schur::{hqr, orthes},
+        LinalgError,
+    },
+    Matrix,
+};
+use num_traits::Float;
+
+// A = V diag(values) V^T for a symmetric A. The eigenvalues are sorted ascending
+// and the columns of V are the matching orthonormal eigenvectors.
+#[derive(Clone, Debug)]
+pub struct SymmetricEigen<T> {
+    values: Matrix<T>,
+    vectors: Matrix<T>,
+}
+
+impl<T> SymmetricEigen<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Result<Self, LinalgError> {
+        let (d, v) = symmetric_eigen(mat, true)?;
+        Ok(Self {
+            values: Matrix::from_vec_col(d),
+            vectors: v,
+        })
+    }
+
+    pub fn values(&self) -> &Matrix<T> {
+        &self.values
+    }
+
+    pub fn vectors(&self) -> &Matrix<T> {
+        &self.vectors
+    }
+}
+
+fn symmetric_eigen<T>(mat: &Matrix<T>, vectors: bool) -> Result<(Vec<T>, Matrix<T>), LinalgError>
+where
+    T: Float,
+{
+    mat.check_square()?;
+    let n = mat.rows;
+    let mut v = mat.clone();
+    let mut d = vec![T::zero(); n];
+    let mut e = vec![T::zero(); n];
+    tridiagonalize(&mut v, &mut d, &mut e, vectors);
+    tridiagonal_ql(&mut v, &mut d, &mut e, vectors)?;
+
+    // selection sort keeps the eigenvector columns in step
+    for i in 0..n {
+        let k = (i..n).fold(i, |k, j| if d[j] < d[k] { j } else { k });
+        if k != i {
+            d.swap(i, k);
+            if vectors {
+                for r in 0..n {
+                    v.array.swap(r * n + i, r * n + k);
+                }
+            }
+        }
+    }
+    Ok((d, v))
+}
+
+// Householder reduction to tridiagonal form (diagonal d, subdiagonal e).
+fn tridiagonalize<T>(v: &mut Matrix<T>, d: &mut [T], e: &mut [T], vectors: bool)
+where
+    T: Float,
+{
+    let n = v.rows;
+    let a = &mut v.array;
+    for j in 0..n {
+        d[j] = a[(n - 1) * n + j];
+    }
+
+    for i in (1..n).rev() {
+        let scale = d[..i].iter().fold(T::zero(), |s, x| s + x.abs());
+        let mut h = T::zero();
+        if scale.is_zero() {
+            e[i] = d[i - 1];
+            for j in 0..i {
+                d[j] = a[(i - 1) * n + j];
+                a[i * n + j] = T::zero();
+                a[j * n + i] = T::zero();
+            }
+        } else {
+            for x in d[..i].iter_mut() {
+                *x = *x / scale;
+                h = h + *x * *x;
+            }
+            let mut f = d[i - 1];
+            let mut g = h.sqrt();
+            if f > T::zero() {
+                g = -g;
+            }
+            e[i] = scale * g;
+            h = h - f * g;
+            d[i - 1] = f - g;
+            for x in e[..i].iter_mut() {
+                *x = T::zero();
+            }
+
+            for j in 0..i {
+                f = d[j];
+                a[j * n + i] = f;
+                g = e[j] + a[j * n + j] * f;
+                for k in (j + 1)..i {
+                    g = g + a[k * n + j] * d[k];
+                    e[k] = e[k] + a[k * n + j] * f;
+                }
+                e[j] = g;
+            }
+            f = T::zero();
+            for j in 0..i {
+                e[j] = e[j] / h;
+                f = f + e[j] * d[j];
+            }
+            let hh = f / (h + h);
+            for j in 0..i {
+                e[j] = e[j] - hh * d[j];
+            }
+            for j in 0..i {
+                f = d[j];
+                g = e[j];
+                for k in j..i {
+                    a[k * n + j] = a[k * n + j] - (f * e[k] + g * d[k]);
+                }
+                d[j] = a[(i - 1) * n + j];
+                a[i * n + j] = T::zero();
+            }
+        }
+        d[i] = h;
+    }
+
+    // accumulate the transformations
+    for i in 0..(n - 1) {
+        a[(n - 1) * n + i] = a[i * n + i];
+        a[i * n + i] = T::one();
+        let h = d[i + 1];
+        if vectors && !h.is_zero() {
+            for k in 0..=i {
+                d[k] = a[k * n + i + 1] / h;
+            }
+            for j in 0..=i {
+                let g = (0..=i).fold(T::zero(), |g, k| g + a[k * n + i + 1] * a[k * n + j]);
+                for k in 0..=i {
+                    a[k * n + j] = a[k * n + j] - g * d[k];
+                }
+            }
+        }
+        for k in 0..=i {
+            a[k * n + i + 1] = T::zero();
+        }
+    }
+    for j in 0..n {
+        d[j] = a[(n - 1) * n + j];
+        a[(n - 1) * n + j] = T::zero();
+    }
+    a[(n - 1) * n + n - 1] = T::one();
+    e[0] = T::zero();
+}
+
+// Implicit QL iterations on the symmetric tridiagonal matrix.
+fn tridiagonal_ql<T>(
+    v: &mut Matrix<T>,
+    d: &mut [T],
+    e: &mut [T],
+    vectors: bool,
+) -> Result<(), LinalgError>
+where
+    T: Float,
+{
+    let n = v.rows;
+    let a = &mut v.array;
+    for i in 1..n {
+        e[i - 1] = e[i];
+    }
+    e[n - 1] = T::zero();
+
+    let two = T::from(2).unwrap();
+    let eps = T::epsilon();
+    let mut f = T::zero();
+    let mut tst1 = T::zero();
+    for l in 0..n {
+        tst1 = tst1.max(d[l].abs() + e[l].abs());
+        let mut m = l;
+        while m < n - 1 && e[m].abs() > eps * tst1 {
+            m += 1;
+        }
+
+        if m > l {
+            let mut iter = 0;
+            loop {
+                iter += 1;
+                if iter > 30 * n {
+                    return Err(LinalgError::NoConvergence);
+                }
+                let mut g = d[l];
+                let mut p = (d[l + 1] - g) / (two * e[l]);
+                let mut r = p.hypot(T::one());
+                if p < T::zero() {
+                    r = -r;
+                }
+                d[l] = e[l] / (p + r);
+                d[l + 1] = e[l] * (p + r);
+                let dl1 = d[l + 1];
+                let mut h = g - d[l];
+                for x in d[(l + 2)..].iter_mut() {
+                    *x = *x - h;
+                }
+                f = f + h;
+
+                p = d[m];
+                let mut c = T::one();
+                let mut c2 = c;
+                let mut c3 = c;
+                let el1 = e[l + 1];
+                let mut s = T::zero();
+                let mut s2 = T::zero();
+                for i in (l..m).rev() {
+                    c3 = c2;
+                    c2 = c;
+                    s2 = s;
+                    g = c * e[i];
+                    h = c * p;
+                    r = p.hypot(e[i]);
+                    e[i + 1] = s * r;
+                    s = e[i] / r;
+                    c = p / r;
+                    p = c * d[i] - s * g;
+                    d[i + 1] = h + s * (c * g + s * d[i]);
+                    if vectors {
+                        for k in 0..n {
+                            h = a[k * n + i + 1];
+                            a[k * n + i + 1] = s * a[k * n + i] + c * h;
+                            a[k * n + i] = c * a[k * n + i] - s * h;
+                        }
+                    }
+                }
+                p = -s * s2 * c3 * el1 * e[l] / dl1;
+                e[l] = s * p;
+                d[l] = c * p;
+
+                if e[l].abs() <= eps * tst1 {
+                    break;
+                }
+            }
+        }
+        d[l] = d[l] + f;
+        e[l] = T::zero();
+    }
+    Ok(())
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn eigh(&self) -> Result<SymmetricEigen<T>, LinalgError> {
+        SymmetricEigen::new(self)
+    }
+
+    pub fn eigvalsh(&self) -> Result<Matrix<T>, LinalgError> {
+        symmetric_eigen(self, false).map(|(d, _)| Matrix::from_vec_col(d))
+    }
+}
+
+// Eigenvalues of a general real matrix as real and imaginary parts. A complex
+// conjugate pair occupies two consecutive entries, positive imaginary part first.
+// The eigenvectors are packed as real columns: for a pair at (j, j + 1), column j
+// holds the real part and column j + 1 the imaginary part of the eigenvector
+// belonging to real[j] + i imag[j], so that A V = V D.
+#[derive(Clone, Debug)]
+pub struct Eigen<T> {
+    real: Matrix<T>,
+    imag: Matrix<T>,
+    vectors: Option<Matrix<T>>,
+}
+
+impl<T> Eigen<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>, vectors: bool) -> Result<Self, LinalgError> {
+        mat.check_square()?;
+        let n = mat.rows;
+        let (mut h, mut v) = orthes(mat);
+        let mut d = vec![T::zero(); n];
+        let mut e = vec![T::zero(); n];
+        let norm = hqr(&mut h, &mut v, &mut d, &mut e)?;
+        if vectors {
+            back_substitute(&mut h, &mut v, &d, &e, norm);
+        }
+        Ok(Self {
+            real: Matrix::from_vec_col(d),
+            imag: Matrix::from_vec_col(e),
+            vectors: if vectors { Some(v) } else { None },
+        })
+    }
+
+    pub fn real(&self) -> &Matrix<T> {
+        &self.real
+    }
+
+    pub fn imag(&self) -> &Matrix<T> {
+        &self.imag
+    }
+
+    pub fn vectors(&self) -> Option<&Matrix<T>> {
+        self.vectors.as_ref()
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.imag.array.iter().all(|x| x.is_zero())
+    }
+
+    // block diagonal D with A V = V D
+    pub fn d(&self) -> Matrix<T> {
+        let n = self.real.rows;
+        let mut d = Matrix::zero(n, n);
+        for i in 0..n {
+            d.array[i * n + i] = self.real.array[i];
+            let e = self.imag.array[i];
+            if e > T::zero() {
+                d.array[i * n + i + 1] = e;
+            } else if e < T::zero() {
+                d.array[i * n + i - 1] = e;
+            }
+        }
+        d
+    }
+}
+
+fn cdiv<T>(xr: T, xi: T, yr: T, yi: T) -> (T, T)
+where
+    T: Float,
+{
+    if yr.abs() > yi.abs() {
+        let r = yi / yr;
+        let d = yr + r * yi;
+        ((xr + r * xi) / d, (xi - r * xr) / d)
+    } else {
+        let r = yr / yi;
+        let d = yi + r * yr;
+        ((r * xr + xi) / d, (r * xi - xr) / d)
+    }
+}
+
+// Back substitution on the real Schur form followed by the back transformation.
+fn back_substitute<T>(hm: &mut Matrix<T>, vm: &mut Matrix<T>, d: &[T], e: &[T], norm: T)
+where
+    T: Float,
+{
+    if norm.is_zero() {
+        return;
+    }
+    let nn = hm.rows;
+    let h = &mut hm.array;
+    macro_rules! h {
+        ($i: expr, $j: expr) => {
+            h[($i) as usize * nn + ($j) as usize]
+        };
+    }
+
+    let two = T::from(2).unwrap();
+    let eps = T::epsilon();
+    let (mut r, mut s, mut z) = (T::zero(), T::zero(), T::zero());
+    let (mut t, mut w, mut x, mut y);
+
+    for n in (0..nn as isize).rev() {
+        let p = d[n as usize];
+        let q = e[n as usize];
+
+        if q.is_zero() {
+            // real vector
+            let mut l = n;
+            h!(n, n) = T::one();
+            for i in (0..n).rev() {
+                w = h!(i, i) - p;
+                r = (l..=n).fold(T::zero(), |r, j| r + h!(i, j) * h!(j, n));
+                if e[i as usize] < T::zero() {
+                    z = w;
+                    s = r;
+                } else {
+                    l = i;
+                    if e[i as usize].is_zero() {
+                        h!(i, n) = if !w.is_zero() {
+                            -r / w
+                        } else {
+                            -r / (eps * norm)
+                        };
+                    } else {
+                        // solve real equations
+                        x = h!(i, i + 1);
+                        y = h!(i + 1, i);
+                        let di = d[i as usize] - p;
+                        let q = di * di + e[i as usize] * e[i as usize];
+                        t = (x * s - z * r) / q;
+                        h!(i, n) = t;
+                        h!(i + 1, n) = if x.abs() > z.abs() {
+                            (-r - w * t) / x
+                        } else {
+                            (-s - y * t) / z
+                        };
+                    }
+
+                    // overflow control
+                    t = h!(i, n).abs();
+                    if (eps * t) * t > T::one() {
+                        for j in i..=n {
+                            h!(j, n) = h!(j, n) / t;
+                        }
+                    }
+                }
+            }
+        } else if q < T::zero() {
+            // complex vector
+            let mut l = n - 1;
+
+            // the last vector component is imaginary so the matrix is triangular
+            if h!(n, n - 1).abs() > h!(n - 1, n).abs() {
+                h!(n - 1, n - 1) = q / h!(n, n - 1);
+                h!(n - 1, n) = -(h!(n, n) - p) / h!(n, n - 1);
+            } else {
+                let (cr, ci) = cdiv(T::zero(), -h!(n - 1, n), h!(n - 1, n - 1) - p, q);
+                h!(n - 1, n - 1) = cr;
+                h!(n - 1, n) = ci;
+            }
+            h!(n, n - 1) = T::zero();
+            h!(n, n) = T::one();
+
+            for i in (0..(n - 1)).rev() {
+                let (mut ra, mut sa) = (T::zero(), T::zero());
+                for j in l..=n {
+                    ra = ra + h!(i, j) * h!(j, n - 1);
+                    sa = sa + h!(i, j) * h!(j, n);
+                }
+                w = h!(i, i) - p;
+
+                if e[i as usize] < T::zero() {
+                    z = w;
+                    r = ra;
+                    s = sa;
+                } else {
+                    l = i;
+                    if e[i as usize].is_zero() {
+                        let (cr, ci) = cdiv(-ra, -sa, w, q);
+                        h!(i, n - 1) = cr;
+                        h!(i, n) = ci;
+                    } else {
+                        // solve complex equations
+                        x = h!(i, i + 1);
+                        y = h!(i + 1, i);
+                        let di = d[i as usize] - p;
+                        let mut vr = di * di + e[i as usize] * e[i as usize] - q * q;
+                        let vi = di * two * q;
+                        if vr.is_zero() && vi.is_zero() {
+                            vr = eps * norm * (w.abs() + q.abs() + x.abs() + y.abs() + z.abs());
+                        }
+                        let (cr, ci) =
+                            cdiv(x * r - z * ra + q * sa, x * s - z * sa - q * ra, vr, vi);
+                        h!(i, n - 1) = cr;
+                        h!(i, n) = ci;
+                        if x.abs() > z.abs() + q.abs() {
+                            h!(i + 1, n - 1) = (-ra - w * h!(i, n - 1) + q * h!(i, n)) / x;
+                            h!(i + 1, n) = (-sa - w * h!(i, n) - q * h!(i, n - 1)) / x;
+                        } else {
+                            let (cr, ci) = cdiv(-r - y * h!(i, n - 1), -s - y * h!(i, n), z, q);
+                            h!(i + 1, n - 1) = cr;
+                            h!(i + 1, n) = ci;
+                        }
+                    }
+
+                    // overflow control
+                    t = h!(i, n - 1).abs().max(h!(i, n).abs());
+                    if (eps * t) * t > T::one() {
+                        for j in i..=n {
+                            h!(j, n - 1) = h!(j, n - 1) / t;
+                            h!(j, n) = h!(j, n) / t;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // back transformation to the eigenvectors of the original matrix
+    let v = &mut vm.array;
+    for j in (0..nn).rev() {
+        for i in 0..nn {
+            let z = (0..=j).fold(T::zero(), |z, k| z + v[i * nn + k] * h[k * nn + j]);
+            v[i * nn + j] = z;
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn eig(&self) -> Result<Eigen<T>, LinalgError> {
+        Eigen::new(self, true)
+    }
+
+    pub fn eigvals(&self) -> Result<(Matrix<T>, Matrix<T>), LinalgError> {
+        Eigen::new(self, false).map(|eig| (eig.real, eig.imag))
+    }
+}
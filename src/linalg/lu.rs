@@ -0,0 +1,197 @@
+use crate::{
+    error::MatrixError,
+    linalg::{check_rhs, LinalgError},
+    Matrix,
+};
+use num_traits::Float;
+
+// PA = LU with partial pivoting. L (unit lower) and U share one row-major array.
+#[derive(Clone, Debug)]
+pub struct Lu<T> {
+    lu: Matrix<T>,
+    perm: Vec<usize>,
+    odd: bool,
+    singular: bool,
+}
+
+impl<T> Lu<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Self {
+        Self::try_new(mat).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(mat: &Matrix<T>) -> Result<Self, MatrixError> {
+        mat.check_square()?;
+        let n = mat.rows;
+        let mut lu = mat.clone();
+        let mut perm: Vec<_> = (0..n).collect();
+        let mut odd = false;
+        let mut singular = false;
+
+        let scale = mat.array.iter().fold(T::zero(), |m, x| m.max(x.abs()));
+        let tol = scale * T::epsilon() * T::from(n).unwrap();
+
+        for k in 0..n {
+            let p = (k..n).fold(k, |p, i| {
+                if lu.array[i * n + k].abs() > lu.array[p * n + k].abs() {
+                    i
+                } else {
+                    p
+                }
+            });
+            if p != k {
+                for j in 0..n {
+                    lu.array.swap(k * n + j, p * n + j);
+                }
+                perm.swap(k, p);
+                odd = !odd;
+            }
+
+            let pivot = lu.array[k * n + k];
+            if pivot.abs() <= tol {
+                // everything left in the column is at most tol as well; drop it so that
+                // L keeps only proper multipliers
+                singular = true;
+                for i in (k + 1)..n {
+                    lu.array[i * n + k] = T::zero();
+                }
+                continue;
+            }
+            for i in (k + 1)..n {
+                let f = lu.array[i * n + k] / pivot;
+                lu.array[i * n + k] = f;
+                for j in (k + 1)..n {
+                    lu.array[i * n + j] = lu.array[i * n + j] - f * lu.array[k * n + j];
+                }
+            }
+        }
+
+        Ok(Self {
+            lu,
+            perm,
+            odd,
+            singular,
+        })
+    }
+
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn l(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut l = Matrix::eye(n);
+        for i in 0..n {
+            for j in 0..i {
+                l.array[i * n + j] = self.lu.array[i * n + j];
+            }
+        }
+        l
+    }
+
+    pub fn u(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut u = Matrix::zero(n, n);
+        for i in 0..n {
+            for j in i..n {
+                u.array[i * n + j] = self.lu.array[i * n + j];
+            }
+        }
+        u
+    }
+
+    pub fn p(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut p = Matrix::zero(n, n);
+        for (i, &j) in self.perm.iter().enumerate() {
+            p.array[i * n + j] = T::one();
+        }
+        p
+    }
+
+    pub fn det(&self) -> T {
+        let n = self.lu.rows;
+        let det = (0..n).fold(T::one(), |d, i| d * self.lu.array[i * n + i]);
+        if self.odd {
+            -det
+        } else {
+            det
+        }
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        check_rhs(self.lu.shape(), b)?;
+        let n = self.lu.rows;
+        if self.singular {
+            return Err(LinalgError::Singular);
+        }
+
+        // each row of x holds one unknown for every right-hand side
+        let k = b.cols;
+        let mut x = Matrix::zero(n, k);
+        for (i, &p) in self.perm.iter().enumerate() {
+            x.array[i * k..(i + 1) * k].copy_from_slice(&b.array[p * k..(p + 1) * k]);
+        }
+
+        for i in 0..n {
+            for j in 0..i {
+                let l = self.lu.array[i * n + j];
+                for c in 0..k {
+                    x.array[i * k + c] = x.array[i * k + c] - l * x.array[j * k + c];
+                }
+            }
+        }
+        for i in (0..n).rev() {
+            for j in (i + 1)..n {
+                let u = self.lu.array[i * n + j];
+                for c in 0..k {
+                    x.array[i * k + c] = x.array[i * k + c] - u * x.array[j * k + c];
+                }
+            }
+            let d = self.lu.array[i * n + i];
+            for c in 0..k {
+                x.array[i * k + c] = x.array[i * k + c] / d;
+            }
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T>, LinalgError> {
+        self.solve(&Matrix::eye(self.lu.rows))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn lu(&self) -> Lu<T> {
+        Lu::new(self)
+    }
+
+    pub fn try_lu(&self) -> Result<Lu<T>, MatrixError> {
+        Lu::try_new(self)
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        self.try_lu()?.solve(b)
+    }
+
+    pub fn det(&self) -> T {
+        self.lu().det()
+    }
+
+    pub fn try_det(&self) -> Result<T, MatrixError> {
+        self.try_lu().map(|lu| lu.det())
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T>, LinalgError> {
+        self.try_lu()?.inverse()
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    error::MatrixError,
+    linalg::{check_rhs, LinalgError},
+    Matrix,
+};
+use num_traits::Float;
+
+// AP = QR by Householder reflections. R is stored in the upper triangle and the
+// reflector vectors (with an implicit leading 1) below the diagonal.
+#[derive(Clone, Debug)]
+pub struct Qr<T> {
+    qr: Matrix<T>,
+    tau: Vec<T>,
+    perm: Vec<usize>,
+    rank: usize,
+    pivoted: bool,
+}
+
+impl<T> Qr<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Self {
+        Self::factorize(mat, false)
+    }
+
+    pub fn with_pivoting(mat: &Matrix<T>) -> Self {
+        Self::factorize(mat, true)
+    }
+
+    fn factorize(mat: &Matrix<T>, pivoting: bool) -> Self {
+        let (m, n) = (mat.rows, mat.cols);
+        let k = m.min(n);
+        let mut a = mat.clone();
+        let mut tau = Vec::with_capacity(k);
+        let mut perm: Vec<_> = (0..n).collect();
+
+        for j in 0..k {
+            if pivoting {
+                let norm2 = |a: &Matrix<T>, c: usize| {
+                    (j..m).fold(T::zero(), |s, i| {
+                        s + a.array[i * n + c] * a.array[i * n + c]
+                    })
+                };
+                let mut p = j;
+                let mut best = norm2(&a, j);
+                for c in (j + 1)..n {
+                    let v = norm2(&a, c);
+                    if v > best {
+                        p = c;
+                        best = v;
+                    }
+                }
+                if p != j {
+                    for i in 0..m {
+                        a.array.swap(i * n + j, i * n + p);
+                    }
+                    perm.swap(j, p);
+                }
+            }
+
+            let x0 = a.array[j * n + j];
+            let alpha = (j..m)
+                .fold(T::zero(), |s, i| {
+                    s + a.array[i * n + j] * a.array[i * n + j]
+                })
+                .sqrt();
+            if alpha.is_zero() {
+                tau.push(T::zero());
+                continue;
+            }
+            let beta = if x0 > T::zero() { -alpha } else { alpha };
+            let v0 = x0 - beta;
+            for i in (j + 1)..m {
+                a.array[i * n + j] = a.array[i * n + j] / v0;
+            }
+            let t = (beta - x0) / beta;
+            a.array[j * n + j] = beta;
+
+            for c in (j + 1)..n {
+                let s = ((j + 1)..m).fold(a.array[j * n + c], |s, i| {
+                    s + a.array[i * n + j] * a.array[i * n + c]
+                }) * t;
+                a.array[j * n + c] = a.array[j * n + c] - s;
+                for i in (j + 1)..m {
+                    a.array[i * n + c] = a.array[i * n + c] - s * a.array[i * n + j];
+                }
+            }
+            tau.push(t);
+        }
+
+        // With pivoting |R(i, i)| is non-increasing, so the rank is the length of the
+        // leading run above tol. Without it a small diagonal entry can sit anywhere and
+        // only the count of nonzero pivots is meaningful.
+        let rmax = (0..k).fold(T::zero(), |r, i| r.max(a.array[i * n + i].abs()));
+        let tol = rmax * T::epsilon() * T::from(m.max(n)).unwrap();
+        let rank = if pivoting {
+            (0..k)
+                .take_while(|&i| a.array[i * n + i].abs() > tol)
+                .count()
+        } else {
+            (0..k).filter(|&i| a.array[i * n + i].abs() > tol).count()
+        };
+
+        Self {
+            qr: a,
+            tau,
+            perm,
+            rank,
+            pivoted: pivoting,
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn p(&self) -> Matrix<T> {
+        let n = self.qr.cols;
+        let mut p = Matrix::zero(n, n);
+        for (j, &i) in self.perm.iter().enumerate() {
+            p.array[i * n + j] = T::one();
+        }
+        p
+    }
+
+    // applies H_j = I - tau_j v_j v_j^T to every column of mat
+    fn reflect(&self, j: usize, mat: &mut Matrix<T>) {
+        let (m, n) = (self.qr.rows, self.qr.cols);
+        let tau = self.tau[j];
+        if tau.is_zero() {
+            return;
+        }
+        for c in 0..mat.cols {
+            let s = ((j + 1)..m).fold(mat.array[j * mat.cols + c], |s, i| {
+                s + self.qr.array[i * n + j] * mat.array[i * mat.cols + c]
+            }) * tau;
+            mat.array[j * mat.cols + c] = mat.array[j * mat.cols + c] - s;
+            for i in (j + 1)..m {
+                mat.array[i * mat.cols + c] =
+                    mat.array[i * mat.cols + c] - s * self.qr.array[i * n + j];
+            }
+        }
+    }
+
+    fn build_q(&self, cols: usize) -> Matrix<T> {
+        let m = self.qr.rows;
+        let mut q = Matrix::zero(m, cols);
+        for i in 0..cols {
+            q.array[i * cols + i] = T::one();
+        }
+        for j in (0..self.tau.len()).rev() {
+            self.reflect(j, &mut q);
+        }
+        q
+    }
+
+    pub fn q(&self) -> Matrix<T> {
+        self.build_q(self.tau.len())
+    }
+
+    pub fn q_full(&self) -> Matrix<T> {
+        self.build_q(self.qr.rows)
+    }
+
+    fn build_r(&self, rows: usize) -> Matrix<T> {
+        let n = self.qr.cols;
+        let mut r = Matrix::zero(rows, n);
+        for i in 0..rows.min(self.tau.len()) {
+            for j in i..n {
+                r.array[i * n + j] = self.qr.array[i * n + j];
+            }
+        }
+        r
+    }
+
+    pub fn r(&self) -> Matrix<T> {
+        self.build_r(self.tau.len())
+    }
+
+    pub fn r_full(&self) -> Matrix<T> {
+        self.build_r(self.qr.rows)
+    }
+
+    pub fn qt_mul(&self, b: &Matrix<T>) -> Matrix<T> {
+        check_rhs(self.qr.shape(), b).unwrap_or_else(|e| panic!("{}", e));
+        let mut y = b.clone();
+        for j in 0..self.tau.len() {
+            self.reflect(j, &mut y);
+        }
+        y
+    }
+
+    // Without pivoting a rank-deficient R cannot be split into a basic block, so that
+    // case is reported as singular; use with_pivoting for such systems.
+    pub fn lstsq(&self, b: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        check_rhs(self.qr.shape(), b)?;
+        if !self.pivoted && self.rank < self.qr.cols {
+            return Err(LinalgError::Singular);
+        }
+        Ok(self.basic_solution(b))
+    }
+
+    // basic least-squares solution: the trailing n - rank unknowns are set to zero
+    fn basic_solution(&self, b: &Matrix<T>) -> Matrix<T> {
+        let n = self.qr.cols;
+        let k = b.cols;
+        let y = self.qt_mul(b);
+
+        let r = self.rank;
+        let mut z = Matrix::zero(r, k);
+        for i in (0..r).rev() {
+            for c in 0..k {
+                let s = ((i + 1)..r).fold(y.array[i * k + c], |s, j| {
+                    s - self.qr.array[i * n + j] * z.array[j * k + c]
+                });
+                z.array[i * k + c] = s / self.qr.array[i * n + i];
+            }
+        }
+
+        let mut x = Matrix::zero(n, k);
+        for i in 0..r {
+            let p = self.perm[i];
+            x.array[p * k..(p + 1) * k].copy_from_slice(&z.array[i * k..(i + 1) * k]);
+        }
+        x
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn qr(&self) -> Qr<T> {
+        Qr::new(self)
+    }
+
+    pub fn qr_pivoted(&self) -> Qr<T> {
+        Qr::with_pivoting(self)
+    }
+
+    pub fn lstsq(&self, b: &Matrix<T>) -> Matrix<T> {
+        self.try_lstsq(b).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_lstsq(&self, b: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        check_rhs(self.shape(), b)?;
+        Ok(self.qr_pivoted().basic_solution(b))
+    }
+}
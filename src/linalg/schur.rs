@@ -0,0 +1,450 @@
+use crate::{error::MatrixError, linalg::LinalgError, Matrix};
+use num_traits::Float;
+
+// A = Q H Q^T with H upper Hessenberg.
+#[derive(Clone, Debug)]
+pub struct Hessenberg<T> {
+    h: Matrix<T>,
+    q: Matrix<T>,
+}
+
+impl<T> Hessenberg<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Self {
+        Self::try_new(mat).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(mat: &Matrix<T>) -> Result<Self, MatrixError> {
+        mat.check_square()?;
+        let (mut h, q) = orthes(mat);
+        let n = h.rows;
+        for i in 0..n {
+            for j in 0..(i.max(1) - 1) {
+                h.array[i * n + j] = T::zero();
+            }
+        }
+        Ok(Self { h, q })
+    }
+
+    pub fn h(&self) -> &Matrix<T> {
+        &self.h
+    }
+
+    pub fn q(&self) -> &Matrix<T> {
+        &self.q
+    }
+}
+
+// A = Z T Z^T with T quasi upper triangular. Complex conjugate eigenvalue pairs
+// remain as 2x2 blocks on the diagonal of T.
+#[derive(Clone, Debug)]
+pub struct Schur<T> {
+    t: Matrix<T>,
+    z: Matrix<T>,
+    real: Matrix<T>,
+    imag: Matrix<T>,
+}
+
+impl<T> Schur<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Result<Self, LinalgError> {
+        mat.check_square()?;
+        let (mut t, mut z) = orthes(mat);
+        let n = t.rows;
+        let mut d = vec![T::zero(); n];
+        let mut e = vec![T::zero(); n];
+        hqr(&mut t, &mut z, &mut d, &mut e)?;
+        clean_schur(&mut t, &e);
+        Ok(Self {
+            t,
+            z,
+            real: Matrix::from_vec_col(d),
+            imag: Matrix::from_vec_col(e),
+        })
+    }
+
+    pub fn t(&self) -> &Matrix<T> {
+        &self.t
+    }
+
+    pub fn z(&self) -> &Matrix<T> {
+        &self.z
+    }
+
+    pub fn eigenvalues(&self) -> (&Matrix<T>, &Matrix<T>) {
+        (&self.real, &self.imag)
+    }
+}
+
+// Householder reduction to Hessenberg form. The reflector vectors are left below
+// the subdiagonal of the returned H.
+pub(crate) fn orthes<T>(mat: &Matrix<T>) -> (Matrix<T>, Matrix<T>)
+where
+    T: Float,
+{
+    let n = mat.rows;
+    let mut h = mat.clone();
+    let mut v = Matrix::<T>::eye(n);
+    let mut ort = vec![T::zero(); n];
+    if n < 3 {
+        return (h, v);
+    }
+    let high = n - 1;
+    let a = &mut h.array;
+
+    for m in 1..high {
+        let scale = (m..=high).fold(T::zero(), |s, i| s + a[i * n + m - 1].abs());
+        if scale.is_zero() {
+            continue;
+        }
+        let mut hh = T::zero();
+        for i in (m..=high).rev() {
+            ort[i] = a[i * n + m - 1] / scale;
+            hh = hh + ort[i] * ort[i];
+        }
+        let mut g = hh.sqrt();
+        if ort[m] > T::zero() {
+            g = -g;
+        }
+        hh = hh - ort[m] * g;
+        ort[m] = ort[m] - g;
+
+        for j in m..n {
+            let f = (m..=high)
+                .rev()
+                .fold(T::zero(), |f, i| f + ort[i] * a[i * n + j])
+                / hh;
+            for i in m..=high {
+                a[i * n + j] = a[i * n + j] - f * ort[i];
+            }
+        }
+        for i in 0..=high {
+            let f = (m..=high)
+                .rev()
+                .fold(T::zero(), |f, j| f + ort[j] * a[i * n + j])
+                / hh;
+            for j in m..=high {
+                a[i * n + j] = a[i * n + j] - f * ort[j];
+            }
+        }
+        ort[m] = scale * ort[m];
+        a[m * n + m - 1] = scale * g;
+    }
+
+    // accumulate the transformations
+    for m in (1..high).rev() {
+        let hm = a[m * n + m - 1];
+        if hm.is_zero() {
+            continue;
+        }
+        for i in (m + 1)..=high {
+            ort[i] = a[i * n + m - 1];
+        }
+        for j in m..=high {
+            let g = (m..=high).fold(T::zero(), |g, i| g + ort[i] * v.array[i * n + j]);
+            // double division avoids possible underflow
+            let g = (g / ort[m]) / hm;
+            for (i, &o) in ort.iter().enumerate().skip(m) {
+                v.array[i * n + j] = v.array[i * n + j] + g * o;
+            }
+        }
+    }
+    (h, v)
+}
+
+// zeroes everything below the quasi triangular structure described by e
+fn clean_schur<T>(t: &mut Matrix<T>, e: &[T])
+where
+    T: Float,
+{
+    let n = t.rows;
+    for i in 1..n {
+        for j in 0..(i - 1) {
+            t.array[i * n + j] = T::zero();
+        }
+        if !(e[i - 1] > T::zero() && e[i] < T::zero()) {
+            t.array[i * n + i - 1] = T::zero();
+        }
+    }
+}
+
+// Francis double shift QR on a Hessenberg matrix. H is reduced to the real Schur
+// form, the transformations are accumulated into V, and the eigenvalues are
+// written to d (real part) and e (imaginary part). Returns the norm of the input.
+pub(crate) fn hqr<T>(
+    hm: &mut Matrix<T>,
+    vm: &mut Matrix<T>,
+    d: &mut [T],
+    e: &mut [T],
+) -> Result<T, LinalgError>
+where
+    T: Float,
+{
+    let nn = hm.rows;
+    let h = &mut hm.array;
+    let v = &mut vm.array;
+    macro_rules! h {
+        ($i: expr, $j: expr) => {
+            h[($i) as usize * nn + ($j) as usize]
+        };
+    }
+    macro_rules! v {
+        ($i: expr, $j: expr) => {
+            v[($i) as usize * nn + ($j) as usize]
+        };
+    }
+
+    let two = T::from(2).unwrap();
+    let eps = T::epsilon();
+    let nni = nn as isize;
+    let mut n = nni - 1;
+    let mut exshift = T::zero();
+    let (mut p, mut q, mut r) = (T::zero(), T::zero(), T::zero());
+    let (mut s, mut w, mut x, mut y, mut z);
+
+    let mut norm = T::zero();
+    for i in 0..nni {
+        for j in (i - 1).max(0)..nni {
+            norm = norm + h!(i, j).abs();
+        }
+    }
+
+    let mut iter = 0;
+    while n >= 0 {
+        // look for a single small subdiagonal element
+        let mut l = n;
+        while l > 0 {
+            s = h!(l - 1, l - 1).abs() + h!(l, l).abs();
+            if s.is_zero() {
+                s = norm;
+            }
+            if h!(l, l - 1).abs() < eps * s {
+                break;
+            }
+            l -= 1;
+        }
+
+        if l == n {
+            // one root found
+            h!(n, n) = h!(n, n) + exshift;
+            d[n as usize] = h!(n, n);
+            e[n as usize] = T::zero();
+            n -= 1;
+            iter = 0;
+        } else if l == n - 1 {
+            // two roots found
+            w = h!(n, n - 1) * h!(n - 1, n);
+            p = (h!(n - 1, n - 1) - h!(n, n)) / two;
+            q = p * p + w;
+            z = q.abs().sqrt();
+            h!(n, n) = h!(n, n) + exshift;
+            h!(n - 1, n - 1) = h!(n - 1, n - 1) + exshift;
+            x = h!(n, n);
+
+            if q >= T::zero() {
+                // real pair
+                z = if p >= T::zero() { p + z } else { p - z };
+                d[n as usize - 1] = x + z;
+                d[n as usize] = d[n as usize - 1];
+                if !z.is_zero() {
+                    d[n as usize] = x - w / z;
+                }
+                e[n as usize - 1] = T::zero();
+                e[n as usize] = T::zero();
+                x = h!(n, n - 1);
+                s = x.abs() + z.abs();
+                p = x / s;
+                q = z / s;
+                r = (p * p + q * q).sqrt();
+                p = p / r;
+                q = q / r;
+
+                for j in (n - 1)..nni {
+                    z = h!(n - 1, j);
+                    h!(n - 1, j) = q * z + p * h!(n, j);
+                    h!(n, j) = q * h!(n, j) - p * z;
+                }
+                for i in 0..=n {
+                    z = h!(i, n - 1);
+                    h!(i, n - 1) = q * z + p * h!(i, n);
+                    h!(i, n) = q * h!(i, n) - p * z;
+                }
+                for i in 0..nni {
+                    z = v!(i, n - 1);
+                    v!(i, n - 1) = q * z + p * v!(i, n);
+                    v!(i, n) = q * v!(i, n) - p * z;
+                }
+            } else {
+                // complex pair
+                d[n as usize - 1] = x + p;
+                d[n as usize] = x + p;
+                e[n as usize - 1] = z;
+                e[n as usize] = -z;
+            }
+            n -= 2;
+            iter = 0;
+        } else {
+            // form shift
+            x = h!(n, n);
+            y = T::zero();
+            w = T::zero();
+            if l < n {
+                y = h!(n - 1, n - 1);
+                w = h!(n, n - 1) * h!(n - 1, n);
+            }
+
+            // Wilkinson's original ad hoc shift
+            if iter == 10 {
+                exshift = exshift + x;
+                for i in 0..=n {
+                    h!(i, i) = h!(i, i) - x;
+                }
+                s = h!(n, n - 1).abs() + h!(n - 1, n - 2).abs();
+                x = T::from(0.75).unwrap() * s;
+                y = x;
+                w = T::from(-0.4375).unwrap() * s * s;
+            }
+
+            // MATLAB's ad hoc shift
+            if iter == 30 {
+                s = (y - x) / two;
+                s = s * s + w;
+                if s > T::zero() {
+                    s = s.sqrt();
+                    if y < x {
+                        s = -s;
+                    }
+                    s = x - w / ((y - x) / two + s);
+                    for i in 0..=n {
+                        h!(i, i) = h!(i, i) - s;
+                    }
+                    exshift = exshift + s;
+                    x = T::from(0.964).unwrap();
+                    y = x;
+                    w = x;
+                }
+            }
+
+            iter += 1;
+            if iter > 30 * nn.max(4) {
+                return Err(LinalgError::NoConvergence);
+            }
+
+            // look for two consecutive small subdiagonal elements
+            let mut m = n - 2;
+            while m >= l {
+                z = h!(m, m);
+                r = x - z;
+                s = y - z;
+                p = (r * s - w) / h!(m + 1, m) + h!(m, m + 1);
+                q = h!(m + 1, m + 1) - z - r - s;
+                r = h!(m + 2, m + 1);
+                s = p.abs() + q.abs() + r.abs();
+                p = p / s;
+                q = q / s;
+                r = r / s;
+                if m == l {
+                    break;
+                }
+                if h!(m, m - 1).abs() * (q.abs() + r.abs())
+                    < eps * (p.abs() * (h!(m - 1, m - 1).abs() + z.abs() + h!(m + 1, m + 1).abs()))
+                {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in (m + 2)..=n {
+                h!(i, i - 2) = T::zero();
+                if i > m + 2 {
+                    h!(i, i - 3) = T::zero();
+                }
+            }
+
+            // double QR step involving rows l..=n and columns m..=n
+            for k in m..n {
+                let notlast = k != n - 1;
+                if k != m {
+                    p = h!(k, k - 1);
+                    q = h!(k + 1, k - 1);
+                    r = if notlast { h!(k + 2, k - 1) } else { T::zero() };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x.is_zero() {
+                        continue;
+                    }
+                    p = p / x;
+                    q = q / x;
+                    r = r / x;
+                }
+
+                s = (p * p + q * q + r * r).sqrt();
+                if p < T::zero() {
+                    s = -s;
+                }
+                if !s.is_zero() {
+                    if k != m {
+                        h!(k, k - 1) = -s * x;
+                    } else if l != m {
+                        h!(k, k - 1) = -h!(k, k - 1);
+                    }
+                    p = p + s;
+                    x = p / s;
+                    y = q / s;
+                    z = r / s;
+                    q = q / p;
+                    r = r / p;
+
+                    for j in k..nni {
+                        p = h!(k, j) + q * h!(k + 1, j);
+                        if notlast {
+                            p = p + r * h!(k + 2, j);
+                            h!(k + 2, j) = h!(k + 2, j) - p * z;
+                        }
+                        h!(k, j) = h!(k, j) - p * x;
+                        h!(k + 1, j) = h!(k + 1, j) - p * y;
+                    }
+                    for i in 0..=n.min(k + 3) {
+                        p = x * h!(i, k) + y * h!(i, k + 1);
+                        if notlast {
+                            p = p + z * h!(i, k + 2);
+                            h!(i, k + 2) = h!(i, k + 2) - p * r;
+                        }
+                        h!(i, k) = h!(i, k) - p;
+                        h!(i, k + 1) = h!(i, k + 1) - p * q;
+                    }
+                    for i in 0..nni {
+                        p = x * v!(i, k) + y * v!(i, k + 1);
+                        if notlast {
+                            p = p + z * v!(i, k + 2);
+                            v!(i, k + 2) = v!(i, k + 2) - p * r;
+                        }
+                        v!(i, k) = v!(i, k) - p;
+                        v!(i, k + 1) = v!(i, k + 1) - p * q;
+                    }
+                }
+            }
+        }
+    }
+    Ok(norm)
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn hessenberg(&self) -> Hessenberg<T> {
+        Hessenberg::new(self)
+    }
+
+    pub fn try_hessenberg(&self) -> Result<Hessenberg<T>, MatrixError> {
+        Hessenberg::try_new(self)
+    }
+
+    pub fn schur(&self) -> Result<Schur<T>, LinalgError> {
+        Schur::new(self)
+    }
+}
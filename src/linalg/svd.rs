@@ -0,0 +1,270 @@
+use crate::{linalg::qr::Qr, Matrix};
+use num_traits::Float;
+
+// A = U diag(s) V^T with the singular values sorted descending.
+#[derive(Clone, Debug)]
+pub struct Svd<T> {
+    u: Matrix<T>,
+    s: Matrix<T>,
+    v: Matrix<T>,
+}
+
+impl<T> Svd<T>
+where
+    T: Float,
+{
+    pub fn new(mat: &Matrix<T>) -> Self {
+        Self::decompose(mat, false)
+    }
+
+    pub fn new_full(mat: &Matrix<T>) -> Self {
+        Self::decompose(mat, true)
+    }
+
+    fn decompose(mat: &Matrix<T>, full: bool) -> Self {
+        let (u, s, v) = if mat.rows >= mat.cols {
+            let (u, s, v) = jacobi(mat);
+            (u, s, v)
+        } else {
+            let (v, s, u) = jacobi(&mat.transpose());
+            (u, s, v)
+        };
+
+        let m = mat.rows;
+        let n = mat.cols;
+        let k = m.min(n);
+        let tol = tolerance(&s, m, n);
+        let r = s.iter().take_while(|&&x| x > tol).count();
+        // columns belonging to zero singular values are rebuilt as an orthonormal complement
+        let u = complete(&u, r, if full { m } else { k });
+        let v = complete(&v, r, if full { n } else { k });
+
+        Self {
+            u,
+            s: Matrix::from_vec_col(s),
+            v,
+        }
+    }
+
+    pub fn u(&self) -> &Matrix<T> {
+        &self.u
+    }
+
+    pub fn s(&self) -> &Matrix<T> {
+        &self.s
+    }
+
+    pub fn v(&self) -> &Matrix<T> {
+        &self.v
+    }
+
+    pub fn tolerance(&self) -> T {
+        tolerance(&self.s.array, self.u.rows, self.v.rows)
+    }
+
+    pub fn rank(&self, tol: Option<T>) -> usize {
+        let tol = tol.unwrap_or_else(|| self.tolerance());
+        self.s.array.iter().filter(|&&x| x > tol).count()
+    }
+
+    pub fn pinv(&self, tol: Option<T>) -> Matrix<T> {
+        let tol = tol.unwrap_or_else(|| self.tolerance());
+        let (m, n) = (self.u.rows, self.v.rows);
+        let (uc, vc) = (self.u.cols, self.v.cols);
+        let mut ret = Matrix::zero(n, m);
+        for (k, &s) in self.s.array.iter().enumerate() {
+            if s <= tol {
+                continue;
+            }
+            for i in 0..n {
+                let v = self.v.array[i * vc + k] / s;
+                for j in 0..m {
+                    ret.array[i * m + j] = ret.array[i * m + j] + v * self.u.array[j * uc + k];
+                }
+            }
+        }
+        ret
+    }
+}
+
+fn tolerance<T>(s: &[T], rows: usize, cols: usize) -> T
+where
+    T: Float,
+{
+    let max = s.first().copied().unwrap_or_else(T::zero);
+    max * T::epsilon() * T::from(rows.max(cols)).unwrap()
+}
+
+// One-sided Jacobi (Hestenes) on the columns of a tall matrix.
+// Returns the thin U, the singular values and the square V.
+fn jacobi<T>(mat: &Matrix<T>) -> (Matrix<T>, Vec<T>, Matrix<T>)
+where
+    T: Float,
+{
+    let (m, n) = (mat.rows, mat.cols);
+    let mut u = mat.clone();
+    let mut v = Matrix::<T>::eye(n);
+    let two = T::from(2).unwrap();
+
+    // convergence is quadratic, the sweep limit is only a guard
+    for _ in 0..60 {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let (mut alpha, mut beta, mut gamma) = (T::zero(), T::zero(), T::zero());
+                for i in 0..m {
+                    let (x, y) = (u.array[i * n + p], u.array[i * n + q]);
+                    alpha = alpha + x * x;
+                    beta = beta + y * y;
+                    gamma = gamma + x * y;
+                }
+                if gamma.is_zero() || gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                let zeta = (beta - alpha) / (two * gamma);
+                let t = zeta.signum() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+                let c = T::one() / (T::one() + t * t).sqrt();
+                let s = c * t;
+                for a in [&mut u, &mut v] {
+                    for i in 0..a.rows {
+                        let (x, y) = (a.array[i * n + p], a.array[i * n + q]);
+                        a.array[i * n + p] = c * x - s * y;
+                        a.array[i * n + q] = s * x + c * y;
+                    }
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let mut s: Vec<_> = (0..n)
+        .map(|j| {
+            (0..m)
+                .fold(T::zero(), |s, i| {
+                    s + u.array[i * n + j] * u.array[i * n + j]
+                })
+                .sqrt()
+        })
+        .collect();
+    for (j, &sj) in s.iter().enumerate() {
+        if !sj.is_zero() {
+            for i in 0..m {
+                u.array[i * n + j] = u.array[i * n + j] / sj;
+            }
+        }
+    }
+
+    // sort descending, moving the columns of U and V along
+    for i in 0..n {
+        let k = (i..n).fold(i, |k, j| if s[j] > s[k] { j } else { k });
+        if k != i {
+            s.swap(i, k);
+            for a in [&mut u, &mut v] {
+                for r in 0..a.rows {
+                    a.array.swap(r * n + i, r * n + k);
+                }
+            }
+        }
+    }
+    (u, s, v)
+}
+
+// keeps the first r columns of mat and fills up to cols with an orthonormal complement
+fn complete<T>(mat: &Matrix<T>, r: usize, cols: usize) -> Matrix<T>
+where
+    T: Float,
+{
+    let m = mat.rows;
+    if r == mat.cols && cols == mat.cols {
+        return mat.clone();
+    }
+    let mut basis = Matrix::zero(m, r);
+    for i in 0..m {
+        basis.array[i * r..(i + 1) * r].copy_from_slice(&mat.array[i * mat.cols..i * mat.cols + r]);
+    }
+    let q = Qr::new(&basis).q_full();
+
+    let mut ret = Matrix::zero(m, cols);
+    for i in 0..m {
+        ret.array[i * cols..i * cols + r].copy_from_slice(&basis.array[i * r..(i + 1) * r]);
+        ret.array[i * cols + r..(i + 1) * cols].copy_from_slice(&q.array[i * m + r..i * m + cols]);
+    }
+    ret
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    pub fn svd(&self) -> Svd<T> {
+        Svd::new(self)
+    }
+
+    pub fn svd_full(&self) -> Svd<T> {
+        Svd::new_full(self)
+    }
+
+    pub fn singular_values(&self) -> Matrix<T> {
+        let (_, s, _) = if self.rows >= self.cols {
+            jacobi(self)
+        } else {
+            jacobi(&self.transpose())
+        };
+        Matrix::from_vec_col(s)
+    }
+
+    pub fn pinv(&self, tol: Option<T>) -> Matrix<T> {
+        self.svd().pinv(tol)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.svd().rank(None)
+    }
+
+    pub fn cond(&self) -> T {
+        let s = self.singular_values();
+        let min = s.array[s.array.len() - 1];
+        if min.is_zero() {
+            T::infinity()
+        } else {
+            s.array[0] / min
+        }
+    }
+
+    // Orthonormal basis as columns; None when the space is only {0}, since a Matrix
+    // cannot have zero columns.
+    pub fn null_space(&self) -> Option<Matrix<T>> {
+        let svd = self.svd_full();
+        let r = svd.rank(None);
+        let v = &svd.v;
+        let (n, k) = (v.rows, v.cols - r);
+        if k == 0 {
+            return None;
+        }
+        let mut ret = Matrix::zero(n, k);
+        for i in 0..n {
+            ret.array[i * k..(i + 1) * k]
+                .copy_from_slice(&v.array[i * v.cols + r..(i + 1) * v.cols]);
+        }
+        Some(ret)
+    }
+
+    pub fn range(&self) -> Option<Matrix<T>> {
+        let svd = self.svd();
+        let r = svd.rank(None);
+        if r == 0 {
+            return None;
+        }
+        let u = &svd.u;
+        let m = u.rows;
+        let mut ret = Matrix::zero(m, r);
+        for i in 0..m {
+            ret.array[i * r..(i + 1) * r].copy_from_slice(&u.array[i * u.cols..i * u.cols + r]);
+        }
+        Some(ret)
+    }
+}
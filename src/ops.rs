@@ -1,8 +1,11 @@
 mod add;
+mod broadcast;
 pub mod float;
+pub mod gemm;
 mod index;
 pub mod matmul;
 pub mod minmax;
+pub mod norm;
 pub mod num;
 mod scalarmul;
 mod sub;
@@ -1,4 +1,10 @@
-use crate::Matrix;
+use crate::{
+    error::MatrixError,
+    ops::broadcast::{broadcast_shape, broadcast_zip, check_assign},
+    sparse::{zip_sparse, SparseMatrix},
+    Matrix,
+};
+use num_traits::Zero;
 use std::ops::{Add, AddAssign};
 
 #[cfg(feature = "mpi")]
@@ -15,8 +21,9 @@ where
     type Output = Matrix<T>;
 
     fn add(self, rhs: &Matrix<T>) -> Self::Output {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "add", |x, y| x + y);
+        }
         let threads = num_cpus::get();
         Self::Output {
             rows: self.rows,
@@ -79,8 +86,9 @@ where
     type Output = Matrix<T>;
 
     fn add(self, rhs: &Matrix<T>) -> Self::Output {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "add", |x, y| x + y);
+        }
         Self::Output {
             rows: self.rows,
             cols: self.cols,
@@ -137,8 +145,12 @@ where
     Vec<T>: FromParallelIterator<T>,
 {
     fn add_assign(&mut self, rhs: &Matrix<T>) {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+        if self.shape() != rhs.shape() {
+            check_assign("add_assign", self.shape(), rhs.shape())
+                .unwrap_or_else(|e| panic!("{}", e));
+            *self = broadcast_zip(self, rhs, "add_assign", |x, y| x + y);
+            return;
+        }
         let threads = num_cpus::get();
         self.array = self
             .array
@@ -167,8 +179,12 @@ where
     T: Add<Output = T> + Copy,
 {
     fn add_assign(&mut self, rhs: &Matrix<T>) {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+        if self.shape() != rhs.shape() {
+            check_assign("add_assign", self.shape(), rhs.shape())
+                .unwrap_or_else(|e| panic!("{}", e));
+            *self = broadcast_zip(self, rhs, "add_assign", |x, y| x + y);
+            return;
+        }
         self.array = self
             .array
             .iter()
@@ -187,3 +203,69 @@ where
         *self += &rhs;
     }
 }
+
+impl<T> Add<&SparseMatrix<T>> for &SparseMatrix<T>
+where
+    T: Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn add(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        zip_sparse(self, rhs, |x, y| match (x, y) {
+            (Some(x), Some(y)) => Some(x + y),
+            (x, y) => x.or(y),
+        })
+    }
+}
+
+impl<T> Add<&SparseMatrix<T>> for SparseMatrix<T>
+where
+    T: Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn add(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        &self + rhs
+    }
+}
+
+impl<T> Add<SparseMatrix<T>> for &SparseMatrix<T>
+where
+    T: Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn add(self, rhs: SparseMatrix<T>) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl<T> Add<SparseMatrix<T>> for SparseMatrix<T>
+where
+    T: Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn add(self, rhs: SparseMatrix<T>) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl<T> Matrix<T> {
+    pub fn try_add<'a>(&'a self, rhs: &'a Matrix<T>) -> Result<Matrix<T>, MatrixError>
+    where
+        &'a Matrix<T>: Add<&'a Matrix<T>, Output = Matrix<T>>,
+    {
+        broadcast_shape("add", self.shape(), rhs.shape())?;
+        Ok(self + rhs)
+    }
+
+    pub fn try_add_assign(&mut self, rhs: &Matrix<T>) -> Result<(), MatrixError>
+    where
+        for<'a> Matrix<T>: AddAssign<&'a Matrix<T>>,
+    {
+        check_assign("add_assign", self.shape(), rhs.shape())?;
+        *self += rhs;
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{error::MatrixError, Matrix};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// NumPy-style broadcasting for 2-D operands: each dimension has to agree or be 1,
+// and a dimension of 1 is repeated along the other operand.
+pub(crate) fn broadcast_shape(
+    op: &'static str,
+    lhs: (usize, usize),
+    rhs: (usize, usize),
+) -> Result<(usize, usize), MatrixError> {
+    let dim = |l: usize, r: usize| match (l, r) {
+        (l, r) if l == r => Some(l),
+        (1, r) => Some(r),
+        (l, 1) => Some(l),
+        _ => None,
+    };
+    match (dim(lhs.0, rhs.0), dim(lhs.1, rhs.1)) {
+        (Some(rows), Some(cols)) => Ok((rows, cols)),
+        _ => Err(MatrixError::ShapeMismatch { op, lhs, rhs }),
+    }
+}
+
+// index of entry (i, j) of the broadcast result in mat
+fn source(mat: &Matrix<impl Sized>, i: usize, j: usize) -> usize {
+    let i = if mat.rows == 1 { 0 } else { i };
+    let j = if mat.cols == 1 { 0 } else { j };
+    i * mat.cols + j
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn broadcast_zip<T, F>(
+    lhs: &Matrix<T>,
+    rhs: &Matrix<T>,
+    op: &'static str,
+    f: F,
+) -> Matrix<T>
+where
+    T: Copy,
+    F: Fn(T, T) -> T,
+{
+    let (rows, cols) =
+        broadcast_shape(op, lhs.shape(), rhs.shape()).unwrap_or_else(|e| panic!("{}", e));
+    let array = (0..rows)
+        .flat_map(|i| (0..cols).map(move |j| (source(lhs, i, j), source(rhs, i, j))))
+        .map(|(l, r)| f(lhs.array[l], rhs.array[r]))
+        .collect();
+    Matrix { rows, cols, array }
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn broadcast_zip<T, F>(
+    lhs: &Matrix<T>,
+    rhs: &Matrix<T>,
+    op: &'static str,
+    f: F,
+) -> Matrix<T>
+where
+    T: Copy + Send + Sync,
+    F: Fn(T, T) -> T + Send + Sync,
+{
+    let (rows, cols) =
+        broadcast_shape(op, lhs.shape(), rhs.shape()).unwrap_or_else(|e| panic!("{}", e));
+    let array = (0..rows)
+        .into_par_iter()
+        .flat_map_iter(|i| (0..cols).map(move |j| (source(lhs, i, j), source(rhs, i, j))))
+        .map(|(l, r)| f(lhs.array[l], rhs.array[r]))
+        .collect();
+    Matrix { rows, cols, array }
+}
+
+// In-place variant: rhs has to broadcast to the shape of lhs, which does not change.
+pub(crate) fn check_assign(
+    op: &'static str,
+    lhs: (usize, usize),
+    rhs: (usize, usize),
+) -> Result<(), MatrixError> {
+    match broadcast_shape(op, lhs, rhs) {
+        Ok(shape) if shape == lhs => Ok(()),
+        _ => Err(MatrixError::ShapeMismatch { op, lhs, rhs }),
+    }
+}
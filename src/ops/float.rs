@@ -168,7 +168,6 @@ where
 
     // TODO! rayon化
     pub fn normalize1(&self, axis: Option<Axis>) -> Self {
-        let threads = num_cpus::get();
         match axis {
             Some(Axis::ROW) => Self {
                 rows: self.rows,
@@ -176,11 +175,10 @@ where
                 array: self
                     .array
                     .chunks(self.cols)
-                    .map(|arr| {
+                    .flat_map(|arr| {
                         arr.iter()
                             .map(|&x| x / arr.iter().fold(T::zero(), |first, x| first + *x))
                     })
-                    .flatten()
                     .collect(),
             },
             Some(Axis::COLUMN) => self.transpose().normalize1(Some(Axis::ROW)).transpose(),
@@ -197,7 +195,6 @@ where
     }
 
     pub fn normalize2(&self, axis: Option<Axis>) -> Self {
-        let threads = num_cpus::get();
         match axis {
             Some(Axis::ROW) => Self {
                 rows: self.rows,
@@ -205,7 +202,7 @@ where
                 array: self
                     .array
                     .chunks(self.cols)
-                    .map(|arr| {
+                    .flat_map(|arr| {
                         arr.iter().map(|&x| {
                             x / arr
                                 .iter()
@@ -213,7 +210,6 @@ where
                                 .sqrt()
                         })
                     })
-                    .flatten()
                     .collect(),
             },
             Some(Axis::COLUMN) => self.transpose().normalize2(Some(Axis::ROW)).transpose(),
@@ -335,11 +331,10 @@ where
                 array: self
                     .array
                     .chunks(self.cols)
-                    .map(|arr| {
+                    .flat_map(|arr| {
                         arr.iter()
                             .map(|&x| x / arr.iter().fold(T::zero(), |first, x| first + *x))
                     })
-                    .flatten()
                     .collect(),
             },
             Some(Axis::COLUMN) => self.transpose().normalize1(Some(Axis::ROW)).transpose(),
@@ -363,7 +358,7 @@ where
                 array: self
                     .array
                     .chunks(self.cols)
-                    .map(|arr| {
+                    .flat_map(|arr| {
                         arr.iter().map(|&x| {
                             x / arr
                                 .iter()
@@ -371,7 +366,6 @@ where
                                 .sqrt()
                         })
                     })
-                    .flatten()
                     .collect(),
             },
             Some(Axis::COLUMN) => self.transpose().normalize2(Some(Axis::ROW)).transpose(),
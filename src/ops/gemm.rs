@@ -0,0 +1,469 @@
+use crate::Matrix;
+use num_traits::Zero;
+use std::{
+    any::Any,
+    mem::size_of,
+    ops::{Mul, Range},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// Blocked matrix product in the style of BLIS/GotoBLAS. B is packed into KC x NC panels
+// and A into MC x KC blocks, both stored as slivers of NR columns / MR rows so that the
+// micro-kernel reads contiguous memory and keeps an MR x NR tile of C in registers.
+const KC: usize = 256;
+const MC: usize = 96;
+const NC: usize = 2048;
+
+// Bounds needed by the kernels; rayon builds also require Send + Sync. 'static lets
+// gemm_acc pick the f32/f64 micro-kernels by type.
+#[cfg(feature = "rayon")]
+pub trait Element: Zero + Mul<Output = Self> + Copy + Send + Sync + 'static {}
+#[cfg(feature = "rayon")]
+impl<T> Element for T where T: Zero + Mul<Output = T> + Copy + Send + Sync + 'static {}
+
+#[cfg(not(feature = "rayon"))]
+pub trait Element: Zero + Mul<Output = Self> + Copy + 'static {}
+#[cfg(not(feature = "rayon"))]
+impl<T> Element for T where T: Zero + Mul<Output = T> + Copy + 'static {}
+
+type Kernel<T, const MR: usize, const NR: usize> = fn(&[T], &[T]) -> [[T; NR]; MR];
+
+// A rows x cols matrix whose (i, j) entry is data[i * rs + j * cs].
+#[derive(Clone, Copy)]
+pub(crate) struct Operand<'a, T> {
+    pub(crate) data: &'a [T],
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+    pub(crate) rs: usize,
+    pub(crate) cs: usize,
+}
+
+impl<T> Operand<'_, T>
+where
+    T: Copy,
+{
+    fn at(&self, i: usize, j: usize) -> T {
+        self.data[i * self.rs + j * self.cs]
+    }
+
+    fn sub(&self, rows: Range<usize>, cols: Range<usize>) -> Self {
+        Operand {
+            data: &self.data[rows.start * self.rs + cols.start * self.cs..],
+            rows: rows.len(),
+            cols: cols.len(),
+            rs: self.rs,
+            cs: self.cs,
+        }
+    }
+}
+
+impl<T> Matrix<T> {
+    pub(crate) fn operand(&self) -> Operand<'_, T> {
+        Operand {
+            data: &self.array,
+            rows: self.rows,
+            cols: self.cols,
+            rs: self.cols,
+            cs: 1,
+        }
+    }
+
+    // the transpose, read in place
+    pub(crate) fn operand_t(&self) -> Operand<'_, T> {
+        Operand {
+            data: &self.array,
+            rows: self.cols,
+            cols: self.rows,
+            rs: 1,
+            cs: self.cols,
+        }
+    }
+
+    fn operand_op(&self, trans: bool) -> Operand<'_, T> {
+        if trans {
+            self.operand_t()
+        } else {
+            self.operand()
+        }
+    }
+}
+
+// c = beta * c, where beta = 0 overwrites c so that NaN in the old values do not leak
+fn scale<T>(beta: T, c: &mut [T])
+where
+    T: Element,
+{
+    if beta.is_zero() {
+        c.iter_mut().for_each(|x| *x = T::zero());
+    } else {
+        c.iter_mut().for_each(|x| *x = beta * *x);
+    }
+}
+
+// C = alpha op(A) op(B) + beta C, where op(X) is X or X^T
+pub fn gemm<T>(
+    alpha: T,
+    a: &Matrix<T>,
+    trans_a: bool,
+    b: &Matrix<T>,
+    trans_b: bool,
+    beta: T,
+    c: &mut Matrix<T>,
+) where
+    T: Element,
+{
+    let (a, b) = (a.operand_op(trans_a), b.operand_op(trans_b));
+    assert_eq!(a.cols, b.rows, "The inner dimensions must agree.");
+    assert_eq!((c.rows, c.cols), (a.rows, b.cols), "C has the wrong shape.");
+
+    scale(beta, &mut c.array);
+    gemm_acc(alpha, a, b, &mut c.array, c.cols);
+}
+
+// y = alpha op(A) x + beta y for vectors x and y
+pub fn gemv<T>(alpha: T, a: &Matrix<T>, trans_a: bool, x: &Matrix<T>, beta: T, y: &mut Matrix<T>)
+where
+    T: Element,
+{
+    assert!(x.rows == 1 || x.cols == 1, "x must be a vector.");
+    assert!(y.rows == 1 || y.cols == 1, "y must be a vector.");
+    let (m, n) = if trans_a {
+        (a.cols, a.rows)
+    } else {
+        (a.rows, a.cols)
+    };
+    assert_eq!(x.array.len(), n);
+    assert_eq!(y.array.len(), m);
+
+    scale(beta, &mut y.array);
+    if trans_a {
+        for (row, &xi) in a.array.chunks(a.cols.max(1)).zip(x.array.iter()) {
+            let s = alpha * xi;
+            for (yj, &aij) in y.array.iter_mut().zip(row.iter()) {
+                *yj = *yj + s * aij;
+            }
+        }
+    } else {
+        for (yi, row) in y.array.iter_mut().zip(a.array.chunks(a.cols.max(1))) {
+            let dot = row
+                .iter()
+                .zip(x.array.iter())
+                .fold(T::zero(), |acc, (&aij, &xj)| acc + aij * xj);
+            *yi = *yi + alpha * dot;
+        }
+    }
+}
+
+// A = A + alpha x y^T for vectors x and y
+pub fn ger<T>(alpha: T, x: &Matrix<T>, y: &Matrix<T>, a: &mut Matrix<T>)
+where
+    T: Element,
+{
+    assert!(x.rows == 1 || x.cols == 1, "x must be a vector.");
+    assert!(y.rows == 1 || y.cols == 1, "y must be a vector.");
+    assert_eq!(x.array.len(), a.rows);
+    assert_eq!(y.array.len(), a.cols);
+
+    for (row, &xi) in a.array.chunks_mut(a.cols.max(1)).zip(x.array.iter()) {
+        let s = alpha * xi;
+        for (aij, &yj) in row.iter_mut().zip(y.array.iter()) {
+            *aij = *aij + s * yj;
+        }
+    }
+}
+
+// C = alpha op(A) op(A)^T + beta C, with op(A) = A^T when trans is set. Like BLAS,
+// only the lower (or upper) triangle of C is computed and written; the other one is
+// left untouched.
+pub fn syrk<T>(alpha: T, a: &Matrix<T>, trans: bool, lower: bool, beta: T, c: &mut Matrix<T>)
+where
+    T: Element,
+{
+    let (x, xt) = (a.operand_op(trans), a.operand_op(!trans));
+    assert_eq!((c.rows, c.cols), (x.rows, x.rows), "C has the wrong shape.");
+    let n = c.rows;
+
+    for i in 0..n {
+        let row = if lower {
+            i * n..i * n + i + 1
+        } else {
+            i * n + i..(i + 1) * n
+        };
+        scale(beta, &mut c.array[row]);
+    }
+
+    // Block rows of MC: the part strictly left (right) of the diagonal block is a plain
+    // product, and the diagonal block goes through a buffer so that only its triangle
+    // is added.
+    let mut diag = vec![T::zero(); MC.min(n) * MC.min(n)];
+    for r0 in (0..n).step_by(MC) {
+        let r1 = (r0 + MC).min(n);
+        let rows = x.sub(r0..r1, 0..x.cols);
+        if lower && r0 > 0 {
+            gemm_acc(
+                alpha,
+                rows,
+                xt.sub(0..xt.rows, 0..r0),
+                &mut c.array[r0 * n..],
+                n,
+            );
+        }
+        if !lower && r1 < n {
+            let cols = xt.sub(0..xt.rows, r1..n);
+            gemm_acc(alpha, rows, cols, &mut c.array[r0 * n + r1..], n);
+        }
+
+        let nb = r1 - r0;
+        let buf = &mut diag[..nb * nb];
+        buf.iter_mut().for_each(|v| *v = T::zero());
+        gemm_acc(alpha, rows, xt.sub(0..xt.rows, r0..r1), buf, nb);
+        for i in 0..nb {
+            let cols = if lower { 0..i + 1 } else { i..nb };
+            for j in cols {
+                let v = &mut c.array[(r0 + i) * n + r0 + j];
+                *v = *v + buf[i * nb + j];
+            }
+        }
+    }
+}
+
+struct PackedB<'a, T> {
+    data: &'a [T],
+    ks: Range<usize>,
+    cols: Range<usize>,
+}
+
+// c[i * ldc + j] += alpha * (A B)(i, j)
+pub(crate) fn gemm_acc<T>(alpha: T, a: Operand<T>, b: Operand<T>, c: &mut [T], ldc: usize)
+where
+    T: Element,
+{
+    assert_eq!(a.cols, b.rows);
+    if a.rows == 0 || b.cols == 0 || a.cols == 0 {
+        return;
+    }
+    assert!(ldc >= b.cols);
+    assert!(c.len() >= (a.rows - 1) * ldc + b.cols);
+
+    // 4-byte elements get twice as many columns per tile since a vector register holds
+    // twice as many of them. f32 and f64 run their own micro-kernels, anything else the
+    // generic one.
+    if size_of::<T>() <= 4 {
+        let kernel = specialized().unwrap_or(micro_kernel::<T, 4, 16>);
+        blocked::<T, 4, 16>(kernel, alpha, a, b, c, ldc);
+    } else {
+        let kernel = specialized().unwrap_or(micro_kernel::<T, 4, 8>);
+        blocked::<T, 4, 8>(kernel, alpha, a, b, c, ldc);
+    }
+}
+
+// the f32/f64 kernel when T is one of them and the tile shape matches
+fn specialized<T, const MR: usize, const NR: usize>() -> Option<Kernel<T, MR, NR>>
+where
+    T: Element,
+{
+    let kernels: [&dyn Any; 2] = [
+        &(micro_f32 as Kernel<f32, 4, 16>),
+        &(micro_f64 as Kernel<f64, 4, 8>),
+    ];
+    kernels.iter().find_map(|k| k.downcast_ref().copied())
+}
+
+fn blocked<T, const MR: usize, const NR: usize>(
+    kernel: Kernel<T, MR, NR>,
+    alpha: T,
+    a: Operand<T>,
+    b: Operand<T>,
+    c: &mut [T],
+    ldc: usize,
+) where
+    T: Element,
+{
+    let (k, n) = (a.cols, b.cols);
+    let mut bp = vec![T::zero(); NC.min(n).div_ceil(NR) * NR * KC.min(k)];
+    for jc in (0..n).step_by(NC) {
+        let cols = jc..(jc + NC).min(n);
+        for pc in (0..k).step_by(KC) {
+            let ks = pc..(pc + KC).min(k);
+            pack_b::<T, NR>(&b, ks.clone(), cols.clone(), &mut bp);
+            let panel = PackedB {
+                data: &bp,
+                ks,
+                cols: cols.clone(),
+            };
+            row_blocks(kernel, alpha, &a, &panel, c, ldc);
+        }
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn row_blocks<T, const MR: usize, const NR: usize>(
+    kernel: Kernel<T, MR, NR>,
+    alpha: T,
+    a: &Operand<T>,
+    panel: &PackedB<T>,
+    c: &mut [T],
+    ldc: usize,
+) where
+    T: Element,
+{
+    let mut ap = vec![T::zero(); MC * panel.ks.len()];
+    for (blk, cc) in c.chunks_mut(MC * ldc).enumerate() {
+        let ic = blk * MC;
+        if ic >= a.rows {
+            break;
+        }
+        let rows = ic..(ic + MC).min(a.rows);
+        pack_a::<T, MR>(alpha, a, rows.clone(), panel.ks.clone(), &mut ap);
+        macro_kernel(kernel, &ap, rows.len(), panel, cc, ldc);
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn row_blocks<T, const MR: usize, const NR: usize>(
+    kernel: Kernel<T, MR, NR>,
+    alpha: T,
+    a: &Operand<T>,
+    panel: &PackedB<T>,
+    c: &mut [T],
+    ldc: usize,
+) where
+    T: Element,
+{
+    c.par_chunks_mut(MC * ldc)
+        .enumerate()
+        .for_each(|(blk, cc)| {
+            let ic = blk * MC;
+            if ic >= a.rows {
+                return;
+            }
+            let rows = ic..(ic + MC).min(a.rows);
+            let mut ap = vec![T::zero(); rows.len().div_ceil(MR) * MR * panel.ks.len()];
+            pack_a::<T, MR>(alpha, a, rows.clone(), panel.ks.clone(), &mut ap);
+            macro_kernel(kernel, &ap, rows.len(), panel, cc, ldc);
+        });
+}
+
+// slivers of NR columns, each stored k-major and padded with zeros
+fn pack_b<T, const NR: usize>(b: &Operand<T>, ks: Range<usize>, cols: Range<usize>, bp: &mut [T])
+where
+    T: Element,
+{
+    let kc = ks.len();
+    for (s, j0) in cols.clone().step_by(NR).enumerate() {
+        let sliver = &mut bp[s * NR * kc..(s + 1) * NR * kc];
+        for (p, dst) in ks.clone().zip(sliver.chunks_exact_mut(NR)) {
+            for (j, x) in dst.iter_mut().enumerate() {
+                *x = if j0 + j < cols.end {
+                    b.at(p, j0 + j)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+// slivers of MR rows scaled by alpha, each stored k-major and padded with zeros
+fn pack_a<T, const MR: usize>(
+    alpha: T,
+    a: &Operand<T>,
+    rows: Range<usize>,
+    ks: Range<usize>,
+    ap: &mut [T],
+) where
+    T: Element,
+{
+    let kc = ks.len();
+    for (s, i0) in rows.clone().step_by(MR).enumerate() {
+        let sliver = &mut ap[s * MR * kc..(s + 1) * MR * kc];
+        for (p, dst) in ks.clone().zip(sliver.chunks_exact_mut(MR)) {
+            for (i, x) in dst.iter_mut().enumerate() {
+                *x = if i0 + i < rows.end {
+                    alpha * a.at(i0 + i, p)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+fn macro_kernel<T, const MR: usize, const NR: usize>(
+    kernel: Kernel<T, MR, NR>,
+    ap: &[T],
+    mc: usize,
+    panel: &PackedB<T>,
+    c: &mut [T],
+    ldc: usize,
+) where
+    T: Element,
+{
+    let kc = panel.ks.len();
+    let nc = panel.cols.len();
+    for jr in (0..nc).step_by(NR) {
+        let bs = &panel.data[jr * kc..(jr + NR) * kc];
+        let nr = NR.min(nc - jr);
+        for ir in (0..mc).step_by(MR) {
+            let tile = kernel(&ap[ir * kc..(ir + MR) * kc], bs);
+            for (i, row) in tile.iter().enumerate().take(MR.min(mc - ir)) {
+                let off = (ir + i) * ldc + panel.cols.start + jr;
+                for (x, &v) in c[off..off + nr].iter_mut().zip(row.iter()) {
+                    *x = *x + v;
+                }
+            }
+        }
+    }
+}
+
+// Rank-1 updates of an MR x NR tile held in fixed-size arrays, which the compiler
+// unrolls and vectorizes.
+fn micro_kernel<T, const MR: usize, const NR: usize>(a: &[T], b: &[T]) -> [[T; NR]; MR]
+where
+    T: Element,
+{
+    let mut tile = [[T::zero(); NR]; MR];
+    for (ap, bp) in a.chunks_exact(MR).zip(b.chunks_exact(NR)) {
+        let ap: &[T; MR] = ap.try_into().unwrap();
+        let bp: &[T; NR] = bp.try_into().unwrap();
+        for (row, &ai) in tile.iter_mut().zip(ap.iter()) {
+            for (x, &bj) in row.iter_mut().zip(bp.iter()) {
+                *x = *x + ai * bj;
+            }
+        }
+    }
+    tile
+}
+
+// The float kernels are written for one tile shape each, so every row of the tile is
+// a fixed-width lane array, and they accumulate with a fused multiply-add when the
+// target has one.
+macro_rules! float_kernel {
+    ($name:ident, $t:ty, $mr:expr, $nr:expr) => {
+        fn $name(a: &[$t], b: &[$t]) -> [[$t; $nr]; $mr] {
+            let mut tile = [[0.; $nr]; $mr];
+            for (ap, bp) in a.chunks_exact($mr).zip(b.chunks_exact($nr)) {
+                let ap: &[$t; $mr] = ap.try_into().unwrap();
+                let bp: &[$t; $nr] = bp.try_into().unwrap();
+                for (row, &ai) in tile.iter_mut().zip(ap.iter()) {
+                    for (x, &bj) in row.iter_mut().zip(bp.iter()) {
+                        #[cfg(target_feature = "fma")]
+                        {
+                            *x = ai.mul_add(bj, *x);
+                        }
+                        #[cfg(not(target_feature = "fma"))]
+                        {
+                            *x += ai * bj;
+                        }
+                    }
+                }
+            }
+            tile
+        }
+    };
+}
+
+float_kernel!(micro_f32, f32, 4, 16);
+float_kernel!(micro_f64, f64, 4, 8);
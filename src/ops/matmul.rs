@@ -1,7 +1,16 @@
-use crate::{sparse::SparseMatrix, Matrix};
+#[cfg(feature = "rayon")]
+use crate::sparse::{balanced_rows, split_rows};
+use crate::{
+    error::MatrixError,
+    ops::broadcast::{broadcast_shape, broadcast_zip},
+    ops::gemm::gemm_acc,
+    sparse::{zip_sparse, SparseMatrix},
+    Matrix,
+};
+use num_traits::{One, Zero};
 use std::{
     iter::Sum,
-    ops::{Div, Mul},
+    ops::{Add, Div, Mul},
 };
 
 #[cfg(feature = "mpi")]
@@ -15,10 +24,15 @@ where
     T: Mul<Output = T> + Copy + Send + Sync,
     Vec<T>: FromParallelIterator<T>,
 {
-    pub fn cwise_mul(&self, rhs: &Matrix<T>) -> Self {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+    pub fn try_cwise_mul(&self, rhs: &Matrix<T>) -> Result<Self, MatrixError> {
+        self.check_cwise(rhs, "cwise_mul")?;
+        Ok(self.cwise_mul(rhs))
+    }
 
+    pub fn cwise_mul(&self, rhs: &Matrix<T>) -> Self {
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "cwise_mul", |x, y| x * y);
+        }
         let threads = num_cpus::get();
         Self {
             rows: self.rows,
@@ -40,10 +54,15 @@ where
     T: Div<Output = T> + Copy + Send + Sync,
     Vec<T>: FromParallelIterator<T>,
 {
-    pub fn cwise_div(&self, rhs: &Matrix<T>) -> Self {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+    pub fn try_cwise_div(&self, rhs: &Matrix<T>) -> Result<Self, MatrixError> {
+        self.check_cwise(rhs, "cwise_div")?;
+        Ok(self.cwise_div(rhs))
+    }
 
+    pub fn cwise_div(&self, rhs: &Matrix<T>) -> Self {
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "cwise_div", |x, y| x / y);
+        }
         let threads = num_cpus::get();
         Self {
             rows: self.rows,
@@ -64,10 +83,15 @@ impl<T> Matrix<T>
 where
     T: Mul<Output = T> + Copy,
 {
-    pub fn cwise_mul(&self, rhs: &Matrix<T>) -> Self {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+    pub fn try_cwise_mul(&self, rhs: &Matrix<T>) -> Result<Self, MatrixError> {
+        self.check_cwise(rhs, "cwise_mul")?;
+        Ok(self.cwise_mul(rhs))
+    }
 
+    pub fn cwise_mul(&self, rhs: &Matrix<T>) -> Self {
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "cwise_mul", |x, y| x * y);
+        }
         Self {
             rows: self.rows,
             cols: self.cols,
@@ -86,10 +110,15 @@ impl<T> Matrix<T>
 where
     T: Div<Output = T> + Copy,
 {
-    pub fn cwise_div(&self, rhs: &Matrix<T>) -> Self {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
+    pub fn try_cwise_div(&self, rhs: &Matrix<T>) -> Result<Self, MatrixError> {
+        self.check_cwise(rhs, "cwise_div")?;
+        Ok(self.cwise_div(rhs))
+    }
 
+    pub fn cwise_div(&self, rhs: &Matrix<T>) -> Self {
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "cwise_div", |x, y| x / y);
+        }
         Self {
             rows: self.rows,
             cols: self.cols,
@@ -106,7 +135,7 @@ where
 #[cfg(feature = "rayon")]
 impl<T> Mul<&Matrix<T>> for &Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy + Send + Sync,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + Send + Sync + 'static,
     Vec<T>: FromParallelIterator<T>,
 {
     type Output = Matrix<T>;
@@ -205,23 +234,14 @@ where
             };
         }
 
-        let array = (0..self.rows)
-            .into_par_iter()
-            .with_min_len(self.rows / threads)
-            .map(|r| {
-                (0..rhs.cols)
-                    .into_par_iter()
-                    .with_min_len(rhs.cols / threads)
-                    .map(move |c| {
-                        self.array[(r * self.cols)..((r + 1) * self.cols)]
-                            .iter()
-                            .zip(0..self.cols)
-                            .map(|(&s, oi)| s * rhs.array[oi * rhs.cols + c])
-                            .sum()
-                    })
-            })
-            .flatten()
-            .collect();
+        let mut array = vec![T::zero(); self.rows * rhs.cols];
+        gemm_acc(
+            T::one(),
+            self.operand(),
+            rhs.operand(),
+            &mut array,
+            rhs.cols,
+        );
 
         Self::Output {
             rows: self.rows,
@@ -234,7 +254,7 @@ where
 #[cfg(feature = "rayon")]
 impl<T> Mul<&Matrix<T>> for Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy + Send + Sync,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + Send + Sync + 'static,
     Vec<T>: FromParallelIterator<T>,
 {
     type Output = Matrix<T>;
@@ -247,7 +267,7 @@ where
 #[cfg(feature = "rayon")]
 impl<T> Mul<Matrix<T>> for &Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy + Send + Sync,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + Send + Sync + 'static,
     Vec<T>: FromParallelIterator<T>,
 {
     type Output = Matrix<T>;
@@ -260,7 +280,7 @@ where
 #[cfg(feature = "rayon")]
 impl<T> Mul<Matrix<T>> for Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy + Send + Sync,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + Send + Sync + 'static,
     Vec<T>: FromParallelIterator<T>,
 {
     type Output = Matrix<T>;
@@ -273,7 +293,7 @@ where
 #[cfg(not(feature = "rayon"))]
 impl<T> Mul<&Matrix<T>> for &Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + 'static,
 {
     type Output = Matrix<T>;
 
@@ -353,18 +373,14 @@ where
             };
         }
 
-        let mut array = Vec::with_capacity(self.rows * rhs.cols);
-        for r in 0..self.rows {
-            for c in 0..rhs.cols {
-                array.push(
-                    self.array[(r * self.cols)..((r + 1) * self.cols)]
-                        .iter()
-                        .zip(0..self.cols)
-                        .map(|(&s, oi)| s * rhs.array[oi * rhs.cols + c])
-                        .sum(),
-                )
-            }
-        }
+        let mut array = vec![T::zero(); self.rows * rhs.cols];
+        gemm_acc(
+            T::one(),
+            self.operand(),
+            rhs.operand(),
+            &mut array,
+            rhs.cols,
+        );
 
         Self::Output {
             rows: self.rows,
@@ -377,7 +393,7 @@ where
 #[cfg(not(feature = "rayon"))]
 impl<T> Mul<&Matrix<T>> for Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + 'static,
 {
     type Output = Matrix<T>;
 
@@ -389,7 +405,7 @@ where
 #[cfg(not(feature = "rayon"))]
 impl<T> Mul<Matrix<T>> for &Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + 'static,
 {
     type Output = Matrix<T>;
 
@@ -401,7 +417,77 @@ where
 #[cfg(not(feature = "rayon"))]
 impl<T> Mul<Matrix<T>> for Matrix<T>
 where
-    T: Sum + Mul<Output = T> + Copy,
+    T: Zero + One + Sum + Mul<Output = T> + Copy + 'static,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> Mul<&Matrix<T>> for &SparseMatrix<T>
+where
+    T: Zero + Sum + Mul<Output = T> + Copy + Send + Sync,
+{
+    type Output = Matrix<T>;
+
+    // rows are split into blocks of roughly equal nnz, one task per block
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows);
+
+        let bounds = balanced_rows(&self.row_ptr, num_cpus::get());
+        let mut array = vec![T::zero(); self.rows * rhs.cols];
+        split_rows(&mut array, &bounds, rhs.cols)
+            .into_par_iter()
+            .zip(bounds.par_windows(2))
+            .for_each(|(out, w)| {
+                for (i, row) in (w[0]..w[1]).zip(out.chunks_mut(rhs.cols.max(1))) {
+                    for (c, y) in row.iter_mut().enumerate() {
+                        *y = (self.row_ptr[i]..self.row_ptr[i + 1])
+                            .map(|j| self.val[j] * rhs.array[self.col_idx[j] * rhs.cols + c])
+                            .sum();
+                    }
+                }
+            });
+
+        Self::Output {
+            rows: self.rows,
+            cols: rhs.cols,
+            array,
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> Mul<&Matrix<T>> for SparseMatrix<T>
+where
+    T: Zero + Sum + Mul<Output = T> + Copy + Send + Sync,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> Mul<Matrix<T>> for &SparseMatrix<T>
+where
+    T: Zero + Sum + Mul<Output = T> + Copy + Send + Sync,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Self::Output {
+        self * &rhs
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> Mul<Matrix<T>> for SparseMatrix<T>
+where
+    T: Zero + Sum + Mul<Output = T> + Copy + Send + Sync,
 {
     type Output = Matrix<T>;
 
@@ -418,21 +504,21 @@ where
     type Output = Matrix<T>;
 
     fn mul(self, rhs: &Matrix<T>) -> Self::Output {
-        assert_eq!(rhs.cols, 1);
         assert_eq!(self.cols, rhs.rows);
 
         let array = (0..self.rows)
-            .into_iter()
-            .map(|i| {
-                (self.row_ptr[i]..self.row_ptr[i + 1])
-                    .map(|j| self.val[j] * rhs.array[self.col_idx[j]])
-                    .sum()
+            .flat_map(|i| {
+                (0..rhs.cols).map(move |c| {
+                    (self.row_ptr[i]..self.row_ptr[i + 1])
+                        .map(|j| self.val[j] * rhs.array[self.col_idx[j] * rhs.cols + c])
+                        .sum()
+                })
             })
             .collect();
 
         Self::Output {
             rows: self.rows,
-            cols: 1,
+            cols: rhs.cols,
             array,
         }
     }
@@ -473,3 +559,183 @@ where
         &self * &rhs
     }
 }
+
+impl<T> Mul<&SparseMatrix<T>> for &Matrix<T>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Copy,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows);
+
+        // row i of the result is the combination of the rows of rhs weighted by row i of self
+        let mut array = vec![T::zero(); self.rows * rhs.cols];
+        for (out, row) in array
+            .chunks_mut(rhs.cols.max(1))
+            .zip(self.array.chunks(self.cols.max(1)))
+        {
+            for (k, &a) in row.iter().enumerate() {
+                for n in rhs.row_ptr[k]..rhs.row_ptr[k + 1] {
+                    out[rhs.col_idx[n]] = out[rhs.col_idx[n]] + a * rhs.val[n];
+                }
+            }
+        }
+
+        Self::Output {
+            rows: self.rows,
+            cols: rhs.cols,
+            array,
+        }
+    }
+}
+
+impl<T> Mul<&SparseMatrix<T>> for Matrix<T>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Copy,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<T> Mul<SparseMatrix<T>> for &Matrix<T>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Copy,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: SparseMatrix<T>) -> Self::Output {
+        self * &rhs
+    }
+}
+
+impl<T> Mul<SparseMatrix<T>> for Matrix<T>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Copy,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: SparseMatrix<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Mul<Output = T> + Zero + Copy,
+{
+    pub fn cwise_mul(&self, rhs: &SparseMatrix<T>) -> Self {
+        zip_sparse(self, rhs, |x, y| x.zip(y).map(|(x, y)| x * y))
+    }
+}
+
+// Gustavson's row-by-row SpGEMM with a dense accumulator.
+impl<T> Mul<&SparseMatrix<T>> for &SparseMatrix<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn mul(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows);
+
+        let mut acc = vec![T::zero(); rhs.cols];
+        let mut marker = vec![usize::MAX; rhs.cols];
+        let mut touched = Vec::new();
+
+        let mut row_ptr = Vec::with_capacity(self.rows + 1);
+        let mut col_idx = Vec::new();
+        let mut val = Vec::new();
+        row_ptr.push(0);
+
+        for i in 0..self.rows {
+            for n in self.row_ptr[i]..self.row_ptr[i + 1] {
+                let (k, a) = (self.col_idx[n], self.val[n]);
+                for m in rhs.row_ptr[k]..rhs.row_ptr[k + 1] {
+                    let j = rhs.col_idx[m];
+                    if marker[j] != i {
+                        marker[j] = i;
+                        acc[j] = a * rhs.val[m];
+                        touched.push(j);
+                    } else {
+                        acc[j] = acc[j] + a * rhs.val[m];
+                    }
+                }
+            }
+            touched.sort_unstable();
+            for &j in touched.iter() {
+                if !acc[j].is_zero() {
+                    col_idx.push(j);
+                    val.push(acc[j]);
+                }
+            }
+            touched.clear();
+            row_ptr.push(col_idx.len());
+        }
+
+        Self::Output {
+            rows: self.rows,
+            cols: rhs.cols,
+            val,
+            col_idx,
+            row_ptr,
+        }
+    }
+}
+
+impl<T> Mul<&SparseMatrix<T>> for SparseMatrix<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn mul(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<T> Mul<SparseMatrix<T>> for &SparseMatrix<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn mul(self, rhs: SparseMatrix<T>) -> Self::Output {
+        self * &rhs
+    }
+}
+
+impl<T> Mul<SparseMatrix<T>> for SparseMatrix<T>
+where
+    T: Mul<Output = T> + Add<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn mul(self, rhs: SparseMatrix<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T> Matrix<T> {
+    // scalars (1 x 1) multiply any matrix, as in Mul
+    pub fn try_matmul<'a>(&'a self, rhs: &'a Matrix<T>) -> Result<Matrix<T>, MatrixError>
+    where
+        &'a Matrix<T>: Mul<&'a Matrix<T>, Output = Matrix<T>>,
+    {
+        if !self.is_scalar() && !rhs.is_scalar() && self.cols != rhs.rows {
+            return Err(MatrixError::ShapeMismatch {
+                op: "matmul",
+                lhs: self.shape(),
+                rhs: rhs.shape(),
+            });
+        }
+        Ok(self * rhs)
+    }
+
+    fn check_cwise(&self, rhs: &Matrix<T>, op: &'static str) -> Result<(), MatrixError> {
+        broadcast_shape(op, self.shape(), rhs.shape()).map(|_| ())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{error::MatrixError, Axis, Matrix};
+use num_traits::{Float, Zero};
+use std::ops::Add;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Norm {
+    Frobenius,
+    One,
+    Inf,
+    MaxAbs,
+    Spectral,
+    Nuclear,
+}
+
+impl<T> Matrix<T>
+where
+    T: Float,
+{
+    fn norm_array(matrix: &Matrix<T>, f: impl Fn(T, T) -> T) -> Vec<T> {
+        matrix
+            .array
+            .chunks(matrix.cols)
+            .map(|s| s.iter().fold(T::zero(), |acc, &x| f(acc, x)))
+            .collect::<Vec<_>>()
+    }
+
+    fn norm_fold(&self, ax: Option<Axis>, f: impl Fn(T, T) -> T) -> Self {
+        match ax {
+            Some(Axis::ROW) => Self::from_vec_col(Self::norm_array(self, f)),
+            Some(Axis::COLUMN) => Self::from_vec_row(Self::norm_array(&self.transpose(), f)),
+            None => Self::new([[self.array.iter().fold(T::zero(), |acc, &x| f(acc, x))]]),
+        }
+    }
+
+    pub fn norm1(&self, ax: Option<Axis>) -> Self {
+        self.norm_fold(ax, |acc, x| acc + x.abs())
+    }
+
+    pub fn norm2(&self, ax: Option<Axis>) -> Self {
+        let mut ret = self.norm_fold(ax, |acc, x| acc + x * x);
+        ret.array.iter_mut().for_each(|x| *x = x.sqrt());
+        ret
+    }
+
+    pub fn norm_max(&self, ax: Option<Axis>) -> Self {
+        self.norm_fold(ax, |acc, x| acc.max(x.abs()))
+    }
+
+    pub fn norm(&self, kind: Norm) -> T {
+        match kind {
+            Norm::Frobenius => self.norm2(None).array[0],
+            Norm::One => self.norm1(Some(Axis::COLUMN)).norm_max(None).array[0],
+            Norm::Inf => self.norm1(Some(Axis::ROW)).norm_max(None).array[0],
+            Norm::MaxAbs => self.norm_max(None).array[0],
+            Norm::Spectral => self.singular_values().array[0],
+            Norm::Nuclear => self
+                .singular_values()
+                .array
+                .iter()
+                .fold(T::zero(), |acc, &x| acc + x),
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Zero + Add<Output = T> + Copy,
+{
+    pub fn trace(&self) -> T {
+        self.try_trace().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_trace(&self) -> Result<T, MatrixError> {
+        self.check_square()?;
+        Ok((0..self.rows).fold(T::zero(), |acc, i| acc + self.array[i * self.cols + i]))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{sparse::SparseMatrix, Matrix};
+use crate::{error::MatrixError, sparse::SparseMatrix, Matrix};
 use num_traits::identities::{One, Zero};
 
 impl<T> Matrix<T>
@@ -18,13 +18,21 @@ where
     }
 
     pub fn diag(&self) -> Self {
-        assert!(self.rows == 1 || self.cols == 1, "Only the vector.");
+        self.try_diag().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_diag(&self) -> Result<Self, MatrixError> {
+        if self.rows != 1 && self.cols != 1 {
+            return Err(MatrixError::NotVector {
+                shape: self.shape(),
+            });
+        }
         let size = if self.rows != 1 { self.rows } else { self.cols };
         let mut ret = Matrix::<T>::zero(size, size);
         for d in 0..size {
             ret.array[d * size + d] = self.array[d].clone();
         }
-        ret
+        Ok(ret)
     }
 
     pub fn diag_row(&self) -> Self {
@@ -1,4 +1,4 @@
-use crate::Matrix;
+use crate::{sparse::SparseMatrix, Matrix};
 use std::ops::{Div, DivAssign, Mul, MulAssign, Neg};
 
 #[cfg(feature = "mpi")]
@@ -58,6 +58,27 @@ macro_rules! defscalarmul {
     };
 }
 
+macro_rules! defscalarmul_sparse {
+    ( $( $t: ty ),+ ) => {
+        $(
+            impl Mul<&SparseMatrix<$t>> for $t {
+                type Output = SparseMatrix<$t>;
+
+                fn mul(self, rhs: &SparseMatrix<$t>) -> Self::Output {
+                    rhs * self
+                }
+            }
+            impl Mul<SparseMatrix<$t>> for $t {
+                type Output = SparseMatrix<$t>;
+
+                fn mul(self, rhs: SparseMatrix<$t>) -> Self::Output {
+                    rhs * self
+                }
+            }
+        )+
+    };
+}
+
 #[cfg(feature = "rayon")]
 impl<T> Neg for &Matrix<T>
 where
@@ -122,6 +143,8 @@ where
     }
 }
 
+defscalarmul_sparse![i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64];
+
 #[cfg(feature = "rayon")]
 defscalarmul_rayon![i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64];
 
@@ -309,3 +332,70 @@ where
         self.array = self.array.iter().map(|&x| x / rhs).collect();
     }
 }
+
+impl<T> Neg for &SparseMatrix<T>
+where
+    T: Neg<Output = T> + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn neg(self) -> Self::Output {
+        Self::Output {
+            rows: self.rows,
+            cols: self.cols,
+            val: self.val.iter().map(|&x| -x).collect(),
+            col_idx: self.col_idx.clone(),
+            row_ptr: self.row_ptr.clone(),
+        }
+    }
+}
+
+impl<T> Neg for SparseMatrix<T>
+where
+    T: Neg<Output = T> + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn neg(mut self) -> Self::Output {
+        self.val.iter_mut().for_each(|x| *x = -*x);
+        self
+    }
+}
+
+impl<T> Mul<T> for &SparseMatrix<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::Output {
+            rows: self.rows,
+            cols: self.cols,
+            val: self.val.iter().map(|&x| x * rhs).collect(),
+            col_idx: self.col_idx.clone(),
+            row_ptr: self.row_ptr.clone(),
+        }
+    }
+}
+
+impl<T> Mul<T> for SparseMatrix<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn mul(mut self, rhs: T) -> Self::Output {
+        self.val.iter_mut().for_each(|x| *x = *x * rhs);
+        self
+    }
+}
+
+impl<T> MulAssign<T> for SparseMatrix<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    fn mul_assign(&mut self, rhs: T) {
+        self.val.iter_mut().for_each(|x| *x = *x * rhs);
+    }
+}
@@ -1,5 +1,11 @@
-use crate::Matrix;
-use std::ops::{Sub, SubAssign};
+use crate::{
+    error::MatrixError,
+    ops::broadcast::{broadcast_shape, broadcast_zip, check_assign},
+    sparse::{zip_sparse, SparseMatrix},
+    Matrix,
+};
+use num_traits::Zero;
+use std::ops::{Neg, Sub, SubAssign};
 
 #[cfg(feature = "mpi")]
 use mpi::traits::*;
@@ -15,9 +21,9 @@ where
     type Output = Matrix<T>;
 
     fn sub(self, rhs: &Matrix<T>) -> Self::Output {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
-
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "sub", |x, y| x - y);
+        }
         let threads = num_cpus::get();
         Self::Output {
             rows: self.rows,
@@ -80,9 +86,9 @@ where
     type Output = Matrix<T>;
 
     fn sub(self, rhs: &Matrix<T>) -> Self::Output {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
-
+        if self.shape() != rhs.shape() {
+            return broadcast_zip(self, rhs, "sub", |x, y| x - y);
+        }
         Self::Output {
             rows: self.rows,
             cols: self.cols,
@@ -139,9 +145,12 @@ where
     Vec<T>: FromParallelIterator<T>,
 {
     fn sub_assign(&mut self, rhs: &Matrix<T>) {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
-
+        if self.shape() != rhs.shape() {
+            check_assign("sub_assign", self.shape(), rhs.shape())
+                .unwrap_or_else(|e| panic!("{}", e));
+            *self = broadcast_zip(self, rhs, "sub_assign", |x, y| x - y);
+            return;
+        }
         let threads = num_cpus::get();
         self.array = self
             .array
@@ -170,9 +179,12 @@ where
     T: Sub<Output = T> + Copy,
 {
     fn sub_assign(&mut self, rhs: &Matrix<T>) {
-        assert_eq!(self.rows, rhs.rows);
-        assert_eq!(self.cols, rhs.cols);
-
+        if self.shape() != rhs.shape() {
+            check_assign("sub_assign", self.shape(), rhs.shape())
+                .unwrap_or_else(|e| panic!("{}", e));
+            *self = broadcast_zip(self, rhs, "sub_assign", |x, y| x - y);
+            return;
+        }
         self.array = self
             .array
             .iter()
@@ -191,3 +203,70 @@ where
         *self -= &rhs;
     }
 }
+
+impl<T> Sub<&SparseMatrix<T>> for &SparseMatrix<T>
+where
+    T: Sub<Output = T> + Neg<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn sub(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        zip_sparse(self, rhs, |x, y| match (x, y) {
+            (Some(x), Some(y)) => Some(x - y),
+            (Some(x), None) => Some(x),
+            (None, y) => y.map(|y| -y),
+        })
+    }
+}
+
+impl<T> Sub<&SparseMatrix<T>> for SparseMatrix<T>
+where
+    T: Sub<Output = T> + Neg<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn sub(self, rhs: &SparseMatrix<T>) -> Self::Output {
+        &self - rhs
+    }
+}
+
+impl<T> Sub<SparseMatrix<T>> for &SparseMatrix<T>
+where
+    T: Sub<Output = T> + Neg<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn sub(self, rhs: SparseMatrix<T>) -> Self::Output {
+        self - &rhs
+    }
+}
+
+impl<T> Sub<SparseMatrix<T>> for SparseMatrix<T>
+where
+    T: Sub<Output = T> + Neg<Output = T> + Zero + Copy,
+{
+    type Output = SparseMatrix<T>;
+
+    fn sub(self, rhs: SparseMatrix<T>) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl<T> Matrix<T> {
+    pub fn try_sub<'a>(&'a self, rhs: &'a Matrix<T>) -> Result<Matrix<T>, MatrixError>
+    where
+        &'a Matrix<T>: Sub<&'a Matrix<T>, Output = Matrix<T>>,
+    {
+        broadcast_shape("sub", self.shape(), rhs.shape())?;
+        Ok(self - rhs)
+    }
+
+    pub fn try_sub_assign(&mut self, rhs: &Matrix<T>) -> Result<(), MatrixError>
+    where
+        for<'a> Matrix<T>: SubAssign<&'a Matrix<T>>,
+    {
+        check_assign("sub_assign", self.shape(), rhs.shape())?;
+        *self -= rhs;
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
-use crate::Matrix;
+use crate::{
+    tensor::{size, Tensor},
+    Matrix,
+};
 
 use rand::distributions::{Distribution, Standard};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
 use rand_distr::uniform::SampleUniform;
@@ -9,11 +12,14 @@ use rand_distr::{Normal, StandardNormal, Uniform};
 
 use num_traits::Float;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 impl<T> Matrix<T>
 where
     Standard: Distribution<T>,
 {
-    pub fn rand(rows: usize, cols: usize, engine: &mut ChaCha8Rng) -> Self {
+    pub fn rand<R: Rng + ?Sized>(rows: usize, cols: usize, engine: &mut R) -> Self {
         Self {
             rows,
             cols,
@@ -22,12 +28,27 @@ where
     }
 }
 
+impl<T> Matrix<T> {
+    // Fills a matrix with draws from any distribution, e.g. Bernoulli, Exp or Gamma.
+    pub fn rand_with<D, R>(dist: D, rows: usize, cols: usize, engine: &mut R) -> Self
+    where
+        D: Distribution<T>,
+        R: Rng + ?Sized,
+    {
+        Self {
+            rows,
+            cols,
+            array: dist.sample_iter(engine).take(rows * cols).collect(),
+        }
+    }
+}
+
 impl<T> Matrix<T>
 where
     T: Float,
     StandardNormal: Distribution<T>,
 {
-    pub fn randn(rows: usize, cols: usize, engine: &mut ChaCha8Rng) -> Self {
+    pub fn randn<R: Rng + ?Sized>(rows: usize, cols: usize, engine: &mut R) -> Self {
         let dist = Normal::<T>::new(T::zero(), T::one()).unwrap();
         Self {
             rows,
@@ -41,7 +62,7 @@ impl<T> Matrix<T>
 where
     T: Float + SampleUniform,
 {
-    pub fn randu(rows: usize, cols: usize, engine: &mut ChaCha8Rng) -> Self {
+    pub fn randu<R: Rng + ?Sized>(rows: usize, cols: usize, engine: &mut R) -> Self {
         let dist = Uniform::<T>::new(T::zero(), T::one());
         Self {
             rows,
@@ -50,3 +71,143 @@ where
         }
     }
 }
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    Standard: Distribution<T>,
+{
+    pub fn rand(dims: [usize; D], engine: &mut ChaCha8Rng) -> Self {
+        Self {
+            dims,
+            array: (0..size(&dims)).map(|_| engine.gen::<T>()).collect(),
+        }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Float,
+    StandardNormal: Distribution<T>,
+{
+    pub fn randn(dims: [usize; D], engine: &mut ChaCha8Rng) -> Self {
+        let dist = Normal::<T>::new(T::zero(), T::one()).unwrap();
+        Self {
+            dims,
+            array: (0..size(&dims)).map(|_| dist.sample(engine)).collect(),
+        }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Float + SampleUniform,
+{
+    pub fn randu(dims: [usize; D], engine: &mut ChaCha8Rng) -> Self {
+        let dist = Uniform::<T>::new(T::zero(), T::one());
+        Self {
+            dims,
+            array: (0..size(&dims)).map(|_| dist.sample(engine)).collect(),
+        }
+    }
+}
+
+const BLOCK: usize = 4096;
+
+// Send + Sync under rayon and nothing otherwise, so that the par_* generators need one
+// set of bounds for both builds. Public in a private module so it can bound the pub
+// impls without being nameable outside the crate.
+mod marker {
+    #[cfg(feature = "rayon")]
+    pub trait MaybeSendSync: Send + Sync {}
+    #[cfg(feature = "rayon")]
+    impl<T> MaybeSendSync for T where T: Send + Sync {}
+
+    #[cfg(not(feature = "rayon"))]
+    pub trait MaybeSendSync {}
+    #[cfg(not(feature = "rayon"))]
+    impl<T> MaybeSendSync for T {}
+}
+use marker::MaybeSendSync;
+
+// The par_* generators draw len samples in blocks of BLOCK entries. Block b comes from
+// stream b of a ChaCha8 generator keyed by the caller's engine, so the values depend only
+// on the seed and not on how the blocks are scheduled across threads. They differ from
+// the serial randn/randu sequences for the same seed.
+fn sample_blocks<T, S, R>(dist: &S, len: usize, engine: &mut R) -> Vec<T>
+where
+    T: MaybeSendSync,
+    S: Distribution<T> + MaybeSendSync,
+    R: Rng + ?Sized,
+{
+    let key = ChaCha8Rng::from_seed(engine.gen());
+    let block = |b: usize| {
+        let mut rng = key.clone();
+        rng.set_stream(b as u64);
+        dist.sample_iter(rng).take(BLOCK.min(len - b * BLOCK))
+    };
+    #[cfg(feature = "rayon")]
+    let samples = (0..len.div_ceil(BLOCK))
+        .into_par_iter()
+        .flat_map_iter(block);
+    #[cfg(not(feature = "rayon"))]
+    let samples = (0..len.div_ceil(BLOCK)).flat_map(block);
+    samples.collect()
+}
+
+impl<T> Matrix<T>
+where
+    T: Float + MaybeSendSync,
+    StandardNormal: Distribution<T>,
+{
+    pub fn par_randn<R: Rng + ?Sized>(rows: usize, cols: usize, engine: &mut R) -> Self {
+        let dist = Normal::<T>::new(T::zero(), T::one()).unwrap();
+        Self {
+            rows,
+            cols,
+            array: sample_blocks(&dist, rows * cols, engine),
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Float + SampleUniform + MaybeSendSync,
+    T::Sampler: MaybeSendSync,
+{
+    pub fn par_randu<R: Rng + ?Sized>(rows: usize, cols: usize, engine: &mut R) -> Self {
+        let dist = Uniform::<T>::new(T::zero(), T::one());
+        Self {
+            rows,
+            cols,
+            array: sample_blocks(&dist, rows * cols, engine),
+        }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Float + MaybeSendSync,
+    StandardNormal: Distribution<T>,
+{
+    pub fn par_randn<R: Rng + ?Sized>(dims: [usize; D], engine: &mut R) -> Self {
+        let dist = Normal::<T>::new(T::zero(), T::one()).unwrap();
+        Self {
+            dims,
+            array: sample_blocks(&dist, size(&dims), engine),
+        }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Float + SampleUniform + MaybeSendSync,
+    T::Sampler: MaybeSendSync,
+{
+    pub fn par_randu<R: Rng + ?Sized>(dims: [usize; D], engine: &mut R) -> Self {
+        let dist = Uniform::<T>::new(T::zero(), T::one());
+        Self {
+            dims,
+            array: sample_blocks(&dist, size(&dims), engine),
+        }
+    }
+}
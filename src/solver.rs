@@ -0,0 +1,156 @@
+mod bicgstab;
+mod cg;
+mod gmres;
+pub mod precond;
+
+pub use bicgstab::bicgstab;
+pub use cg::cg;
+pub use gmres::gmres;
+
+use crate::{sparse::SparseMatrix, Matrix};
+use num_traits::Float;
+use std::ops::Mul;
+
+// Anything that can compute y = A x for a column vector x.
+pub trait LinearOperator<T> {
+    fn rows(&self) -> usize;
+    fn cols(&self) -> usize;
+    fn apply(&self, x: &Matrix<T>) -> Matrix<T>;
+}
+
+impl<T> LinearOperator<T> for Matrix<T>
+where
+    T: Float,
+{
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, x.rows);
+        assert_eq!(x.cols, 1);
+        Matrix::from_vec_col(
+            self.array
+                .chunks(self.cols)
+                .map(|row| dot(row, &x.array))
+                .collect(),
+        )
+    }
+}
+
+impl<T> LinearOperator<T> for SparseMatrix<T>
+where
+    T: Float,
+    for<'a> &'a SparseMatrix<T>: Mul<&'a Matrix<T>, Output = Matrix<T>>,
+{
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn apply(&self, x: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(x.cols, 1);
+        self * x
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Options<T> {
+    pub tol: T,
+    pub max_iter: usize,
+}
+
+impl<T> Default for Options<T>
+where
+    T: Float,
+{
+    fn default() -> Self {
+        Self {
+            tol: T::from(1e-8).unwrap(),
+            max_iter: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Converged,
+    MaxIterations,
+    Breakdown,
+}
+
+// residuals holds ||b - Ax|| / ||b|| after every iteration
+#[derive(Clone, Debug)]
+pub struct SolverResult<T> {
+    pub x: Matrix<T>,
+    pub status: Status,
+    pub iterations: usize,
+    pub residuals: Vec<T>,
+}
+
+impl<T> SolverResult<T> {
+    pub fn converged(&self) -> bool {
+        self.status == Status::Converged
+    }
+}
+
+pub(crate) fn dot<T>(x: &[T], y: &[T]) -> T
+where
+    T: Float,
+{
+    x.iter()
+        .zip(y.iter())
+        .fold(T::zero(), |s, (&a, &b)| s + a * b)
+}
+
+pub(crate) fn norm<T>(x: &[T]) -> T
+where
+    T: Float,
+{
+    dot(x, x).sqrt()
+}
+
+// y += a x
+pub(crate) fn axpy<T>(a: T, x: &[T], y: &mut [T])
+where
+    T: Float,
+{
+    y.iter_mut()
+        .zip(x.iter())
+        .for_each(|(y, &x)| *y = *y + a * x);
+}
+
+pub(crate) fn residual<T, A>(a: &A, b: &Matrix<T>, x: &Matrix<T>) -> Matrix<T>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    let mut r = b.clone();
+    axpy(-T::one(), &a.apply(x).array, &mut r.array);
+    r
+}
+
+pub(crate) fn initial_guess<T, A>(a: &A, b: &Matrix<T>, x0: Option<&Matrix<T>>) -> Matrix<T>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+{
+    assert_eq!(a.rows(), a.cols(), "The operator must be square.");
+    assert_eq!(b.cols, 1, "The right-hand side must be a column vector.");
+    assert_eq!(a.rows(), b.rows);
+    match x0 {
+        Some(x0) => {
+            assert_eq!(x0.rows, b.rows);
+            assert_eq!(x0.cols, 1);
+            x0.clone()
+        }
+        None => Matrix::zero(b.rows, 1),
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    solver::{
+        axpy, dot, initial_guess, norm, precond::Preconditioner, residual, LinearOperator, Options,
+        SolverResult, Status,
+    },
+    Matrix,
+};
+use num_traits::Float;
+
+// Right preconditioned BiCGSTAB for general square systems.
+pub fn bicgstab<T, A, P>(
+    a: &A,
+    b: &Matrix<T>,
+    x0: Option<&Matrix<T>>,
+    m: &P,
+    opts: &Options<T>,
+) -> SolverResult<T>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let mut x = initial_guess(a, b, x0);
+    let n = b.rows;
+    let bnorm = norm(&b.array);
+    let mut residuals = Vec::new();
+    if bnorm.is_zero() {
+        return SolverResult {
+            x: Matrix::zero(n, 1),
+            status: Status::Converged,
+            iterations: 0,
+            residuals,
+        };
+    }
+
+    let mut r = residual(a, b, &x);
+    let r_hat = r.clone();
+    let (mut rho, mut alpha, mut omega) = (T::one(), T::one(), T::one());
+    let mut v = Matrix::zero(n, 1);
+    let mut p = Matrix::zero(n, 1);
+
+    let mut status = Status::MaxIterations;
+    let mut iterations = 0;
+    if norm(&r.array) / bnorm < opts.tol {
+        status = Status::Converged;
+    }
+
+    while status == Status::MaxIterations && iterations < opts.max_iter {
+        iterations += 1;
+        let rho_new = dot(&r_hat.array, &r.array);
+        if rho_new.is_zero() {
+            status = Status::Breakdown;
+            break;
+        }
+        let beta = (rho_new / rho) * (alpha / omega);
+        rho = rho_new;
+        for i in 0..n {
+            p.array[i] = r.array[i] + beta * (p.array[i] - omega * v.array[i]);
+        }
+
+        let p_hat = m.apply(&p);
+        v = a.apply(&p_hat);
+        let rv = dot(&r_hat.array, &v.array);
+        if rv.is_zero() {
+            status = Status::Breakdown;
+            break;
+        }
+        alpha = rho / rv;
+
+        let mut s = r.clone();
+        axpy(-alpha, &v.array, &mut s.array);
+        axpy(alpha, &p_hat.array, &mut x.array);
+        let res = norm(&s.array) / bnorm;
+        if res < opts.tol {
+            residuals.push(res);
+            status = Status::Converged;
+            break;
+        }
+
+        let s_hat = m.apply(&s);
+        let t = a.apply(&s_hat);
+        let tt = dot(&t.array, &t.array);
+        if tt.is_zero() {
+            residuals.push(res);
+            status = Status::Breakdown;
+            break;
+        }
+        omega = dot(&t.array, &s.array) / tt;
+        axpy(omega, &s_hat.array, &mut x.array);
+        r = s;
+        axpy(-omega, &t.array, &mut r.array);
+
+        let res = norm(&r.array) / bnorm;
+        residuals.push(res);
+        if res < opts.tol {
+            status = Status::Converged;
+        } else if omega.is_zero() {
+            status = Status::Breakdown;
+        }
+    }
+
+    SolverResult {
+        x,
+        status,
+        iterations,
+        residuals,
+    }
+}
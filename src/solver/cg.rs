@@ -0,0 +1,81 @@
+use crate::{
+    solver::{
+        axpy, dot, initial_guess, norm, precond::Preconditioner, residual, LinearOperator, Options,
+        SolverResult, Status,
+    },
+    Matrix,
+};
+use num_traits::Float;
+
+// Preconditioned conjugate gradient for symmetric positive definite systems.
+pub fn cg<T, A, P>(
+    a: &A,
+    b: &Matrix<T>,
+    x0: Option<&Matrix<T>>,
+    m: &P,
+    opts: &Options<T>,
+) -> SolverResult<T>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    let mut x = initial_guess(a, b, x0);
+    let bnorm = norm(&b.array);
+    let mut residuals = Vec::new();
+    if bnorm.is_zero() {
+        return SolverResult {
+            x: Matrix::zero(b.rows, 1),
+            status: Status::Converged,
+            iterations: 0,
+            residuals,
+        };
+    }
+
+    let mut r = residual(a, b, &x);
+    let mut z = m.apply(&r);
+    let mut p = z.clone();
+    let mut rz = dot(&r.array, &z.array);
+
+    let mut status = Status::MaxIterations;
+    let mut iterations = 0;
+    if norm(&r.array) / bnorm < opts.tol {
+        status = Status::Converged;
+    }
+
+    while status == Status::MaxIterations && iterations < opts.max_iter {
+        iterations += 1;
+        let ap = a.apply(&p);
+        let pap = dot(&p.array, &ap.array);
+        if pap.is_zero() {
+            status = Status::Breakdown;
+            break;
+        }
+        let alpha = rz / pap;
+        axpy(alpha, &p.array, &mut x.array);
+        axpy(-alpha, &ap.array, &mut r.array);
+
+        let res = norm(&r.array) / bnorm;
+        residuals.push(res);
+        if res < opts.tol {
+            status = Status::Converged;
+            break;
+        }
+
+        z = m.apply(&r);
+        let rz_new = dot(&r.array, &z.array);
+        let beta = rz_new / rz;
+        rz = rz_new;
+        p.array
+            .iter_mut()
+            .zip(z.array.iter())
+            .for_each(|(p, &z)| *p = z + beta * *p);
+    }
+
+    SolverResult {
+        x,
+        status,
+        iterations,
+        residuals,
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    solver::{
+        axpy, dot, initial_guess, norm, precond::Preconditioner, residual, LinearOperator, Options,
+        SolverResult, Status,
+    },
+    Matrix,
+};
+use num_traits::Float;
+
+// Right preconditioned GMRES restarted every `restart` iterations.
+pub fn gmres<T, A, P>(
+    a: &A,
+    b: &Matrix<T>,
+    x0: Option<&Matrix<T>>,
+    m: &P,
+    restart: usize,
+    opts: &Options<T>,
+) -> SolverResult<T>
+where
+    T: Float,
+    A: LinearOperator<T> + ?Sized,
+    P: Preconditioner<T> + ?Sized,
+{
+    assert!(restart != 0, "restart cannot be set to zero.");
+    let mut x = initial_guess(a, b, x0);
+    let n = b.rows;
+    let bnorm = norm(&b.array);
+    let mut residuals = Vec::new();
+    if bnorm.is_zero() {
+        return SolverResult {
+            x: Matrix::zero(n, 1),
+            status: Status::Converged,
+            iterations: 0,
+            residuals,
+        };
+    }
+
+    let mut status = Status::MaxIterations;
+    let mut iterations = 0;
+
+    loop {
+        let r = residual(a, b, &x);
+        let beta = norm(&r.array);
+        if beta / bnorm < opts.tol {
+            status = Status::Converged;
+            break;
+        }
+        if iterations >= opts.max_iter {
+            break;
+        }
+
+        // Arnoldi basis, Hessenberg matrix (column by column) and Givens rotations
+        let mut basis = vec![r.array.iter().map(|&x| x / beta).collect::<Vec<_>>()];
+        let mut h: Vec<Vec<T>> = Vec::with_capacity(restart);
+        let mut cs: Vec<T> = Vec::with_capacity(restart);
+        let mut sn: Vec<T> = Vec::with_capacity(restart);
+        let mut g = vec![beta];
+
+        for j in 0..restart {
+            iterations += 1;
+            let z = m.apply(&Matrix::from_vec_col(basis[j].clone()));
+            let mut w = a.apply(&z).array;
+
+            let mut col = Vec::with_capacity(j + 2);
+            for v in basis.iter() {
+                let hij = dot(&w, v);
+                axpy(-hij, v, &mut w);
+                col.push(hij);
+            }
+            let hnext = norm(&w);
+            col.push(hnext);
+
+            for i in 0..j {
+                let (x, y) = (col[i], col[i + 1]);
+                col[i] = cs[i] * x + sn[i] * y;
+                col[i + 1] = cs[i] * y - sn[i] * x;
+            }
+            let d = col[j].hypot(col[j + 1]);
+            let (c, s) = if d.is_zero() {
+                (T::one(), T::zero())
+            } else {
+                (col[j] / d, col[j + 1] / d)
+            };
+            col[j] = d;
+            col[j + 1] = T::zero();
+            g.push(-s * g[j]);
+            g[j] = c * g[j];
+            cs.push(c);
+            sn.push(s);
+            h.push(col);
+
+            let res = g[j + 1].abs() / bnorm;
+            residuals.push(res);
+            if res < opts.tol {
+                status = Status::Converged;
+            }
+            if status == Status::Converged || iterations >= opts.max_iter || hnext.is_zero() {
+                break;
+            }
+            basis.push(w.iter().map(|&x| x / hnext).collect());
+        }
+
+        // y = H^-1 g, then x += M^-1 V y
+        let k = h.len();
+        let mut y = vec![T::zero(); k];
+        for i in (0..k).rev() {
+            let s = ((i + 1)..k).fold(g[i], |s, l| s - h[l][i] * y[l]);
+            if h[i][i].is_zero() {
+                status = Status::Breakdown;
+                break;
+            }
+            y[i] = s / h[i][i];
+        }
+        if status == Status::Breakdown {
+            break;
+        }
+        let mut update = vec![T::zero(); n];
+        for (v, &yi) in basis.iter().zip(y.iter()) {
+            axpy(yi, v, &mut update);
+        }
+        let update = m.apply(&Matrix::from_vec_col(update));
+        axpy(T::one(), &update.array, &mut x.array);
+
+        if status == Status::Converged {
+            break;
+        }
+    }
+
+    SolverResult {
+        x,
+        status,
+        iterations,
+        residuals,
+    }
+}
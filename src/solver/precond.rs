@@ -0,0 +1,276 @@
+use crate::{linalg::LinalgError, sparse::SparseMatrix, Matrix};
+use num_traits::Float;
+
+// z = M^-1 r for an approximation M of the system matrix.
+pub trait Preconditioner<T> {
+    fn apply(&self, r: &Matrix<T>) -> Matrix<T>;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Identity;
+
+impl<T> Preconditioner<T> for Identity
+where
+    T: Clone,
+{
+    fn apply(&self, r: &Matrix<T>) -> Matrix<T> {
+        r.clone()
+    }
+}
+
+fn diagonal<T>(a: &SparseMatrix<T>) -> Result<Vec<usize>, LinalgError> {
+    assert_eq!(a.rows, a.cols, "The matrix must be square.");
+    (0..a.rows)
+        .map(|i| {
+            (a.row_ptr[i]..a.row_ptr[i + 1])
+                .find(|&n| a.col_idx[n] == i)
+                .ok_or(LinalgError::Singular)
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Jacobi<T> {
+    inv_diag: Vec<T>,
+}
+
+impl<T> Jacobi<T>
+where
+    T: Float,
+{
+    pub fn new(a: &SparseMatrix<T>) -> Result<Self, LinalgError> {
+        let inv_diag = diagonal(a)?
+            .into_iter()
+            .map(|n| {
+                if a.val[n].is_zero() {
+                    Err(LinalgError::Singular)
+                } else {
+                    Ok(a.val[n].recip())
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { inv_diag })
+    }
+}
+
+impl<T> Preconditioner<T> for Jacobi<T>
+where
+    T: Float,
+{
+    fn apply(&self, r: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(r.rows, self.inv_diag.len());
+        Matrix::from_vec_col(
+            r.array
+                .iter()
+                .zip(self.inv_diag.iter())
+                .map(|(&x, &d)| x * d)
+                .collect(),
+        )
+    }
+}
+
+// Incomplete LU without fill-in: L (unit lower) and U share the sparsity pattern of A.
+#[derive(Clone, Debug)]
+pub struct Ilu0<T> {
+    lu: SparseMatrix<T>,
+    diag: Vec<usize>,
+}
+
+impl<T> Ilu0<T>
+where
+    T: Float,
+{
+    pub fn new(a: &SparseMatrix<T>) -> Result<Self, LinalgError> {
+        let diag = diagonal(a)?;
+        let mut lu = a.clone();
+        let mut iw = vec![usize::MAX; a.cols];
+
+        for i in 0..a.rows {
+            for n in lu.row_ptr[i]..lu.row_ptr[i + 1] {
+                iw[lu.col_idx[n]] = n;
+            }
+            for p in lu.row_ptr[i]..diag[i] {
+                let k = lu.col_idx[p];
+                let l = lu.val[p] / lu.val[diag[k]];
+                lu.val[p] = l;
+                for q in (diag[k] + 1)..lu.row_ptr[k + 1] {
+                    let j = iw[lu.col_idx[q]];
+                    if j != usize::MAX {
+                        lu.val[j] = lu.val[j] - l * lu.val[q];
+                    }
+                }
+            }
+            if lu.val[diag[i]].is_zero() {
+                return Err(LinalgError::Singular);
+            }
+            for n in lu.row_ptr[i]..lu.row_ptr[i + 1] {
+                iw[lu.col_idx[n]] = usize::MAX;
+            }
+        }
+        Ok(Self { lu, diag })
+    }
+}
+
+impl<T> Preconditioner<T> for Ilu0<T>
+where
+    T: Float,
+{
+    fn apply(&self, r: &Matrix<T>) -> Matrix<T> {
+        let lu = &self.lu;
+        assert_eq!(r.rows, lu.rows);
+        let mut x = r.array.clone();
+        for i in 0..lu.rows {
+            x[i] =
+                (lu.row_ptr[i]..self.diag[i]).fold(x[i], |s, n| s - lu.val[n] * x[lu.col_idx[n]]);
+        }
+        for i in (0..lu.rows).rev() {
+            let s = ((self.diag[i] + 1)..lu.row_ptr[i + 1])
+                .fold(x[i], |s, n| s - lu.val[n] * x[lu.col_idx[n]]);
+            x[i] = s / lu.val[self.diag[i]];
+        }
+        Matrix::from_vec_col(x)
+    }
+}
+
+// Incomplete Cholesky without fill-in on the lower triangle of a symmetric A.
+// Each row of l ends with its diagonal entry.
+#[derive(Clone, Debug)]
+pub struct Ic0<T> {
+    l: SparseMatrix<T>,
+}
+
+impl<T> Ic0<T>
+where
+    T: Float,
+{
+    pub fn new(a: &SparseMatrix<T>) -> Result<Self, LinalgError> {
+        diagonal(a)?;
+        let mut row_ptr = vec![0];
+        let mut col_idx = Vec::new();
+        let mut val = Vec::new();
+        for i in 0..a.rows {
+            for n in a.row_ptr[i]..a.row_ptr[i + 1] {
+                if a.col_idx[n] <= i {
+                    col_idx.push(a.col_idx[n]);
+                    val.push(a.val[n]);
+                }
+            }
+            row_ptr.push(col_idx.len());
+        }
+
+        for i in 0..a.rows {
+            for p in row_ptr[i]..row_ptr[i + 1] {
+                let j = col_idx[p];
+                // sum of l_ik l_jk over k < j present in both rows
+                let (mut s, mut q) = (val[p], row_ptr[j]);
+                for m in row_ptr[i]..p {
+                    let k = col_idx[m];
+                    while q < row_ptr[j + 1] && col_idx[q] < k {
+                        q += 1;
+                    }
+                    if q < row_ptr[j + 1] && col_idx[q] == k {
+                        s = s - val[m] * val[q];
+                    }
+                }
+                if j < i {
+                    val[p] = s / val[row_ptr[j + 1] - 1];
+                } else if s <= T::zero() || s.is_nan() {
+                    return Err(LinalgError::NotPositiveDefinite);
+                } else {
+                    val[p] = s.sqrt();
+                }
+            }
+        }
+
+        Ok(Self {
+            l: SparseMatrix {
+                rows: a.rows,
+                cols: a.cols,
+                val,
+                col_idx,
+                row_ptr,
+            },
+        })
+    }
+}
+
+impl<T> Preconditioner<T> for Ic0<T>
+where
+    T: Float,
+{
+    fn apply(&self, r: &Matrix<T>) -> Matrix<T> {
+        let l = &self.l;
+        assert_eq!(r.rows, l.rows);
+        let mut x = r.array.clone();
+        for i in 0..l.rows {
+            let d = l.row_ptr[i + 1] - 1;
+            let s = (l.row_ptr[i]..d).fold(x[i], |s, n| s - l.val[n] * x[l.col_idx[n]]);
+            x[i] = s / l.val[d];
+        }
+        for i in (0..l.rows).rev() {
+            let d = l.row_ptr[i + 1] - 1;
+            x[i] = x[i] / l.val[d];
+            for n in l.row_ptr[i]..d {
+                x[l.col_idx[n]] = x[l.col_idx[n]] - l.val[n] * x[i];
+            }
+        }
+        Matrix::from_vec_col(x)
+    }
+}
+
+// Symmetric successive over-relaxation with 0 < omega < 2.
+#[derive(Clone, Debug)]
+pub struct Ssor<T> {
+    a: SparseMatrix<T>,
+    diag: Vec<usize>,
+    omega: T,
+}
+
+impl<T> Ssor<T>
+where
+    T: Float,
+{
+    pub fn new(a: &SparseMatrix<T>, omega: T) -> Result<Self, LinalgError> {
+        assert!(
+            omega > T::zero() && omega < T::from(2).unwrap(),
+            "omega must be in (0, 2)."
+        );
+        let diag = diagonal(a)?;
+        if diag.iter().any(|&n| a.val[n].is_zero()) {
+            return Err(LinalgError::Singular);
+        }
+        Ok(Self {
+            a: a.clone(),
+            diag,
+            omega,
+        })
+    }
+}
+
+impl<T> Preconditioner<T> for Ssor<T>
+where
+    T: Float,
+{
+    fn apply(&self, r: &Matrix<T>) -> Matrix<T> {
+        let a = &self.a;
+        assert_eq!(r.rows, a.rows);
+        let w = self.omega;
+        let mut x = r.array.clone();
+
+        // (D / w + L) y = r
+        for i in 0..a.rows {
+            let s = (a.row_ptr[i]..self.diag[i]).fold(x[i], |s, n| s - a.val[n] * x[a.col_idx[n]]);
+            x[i] = s * w / a.val[self.diag[i]];
+        }
+        // (D / w + U) z = (D / w) y
+        for i in (0..a.rows).rev() {
+            let d = a.val[self.diag[i]] / w;
+            let s = ((self.diag[i] + 1)..a.row_ptr[i + 1])
+                .fold(d * x[i], |s, n| s - a.val[n] * x[a.col_idx[n]]);
+            x[i] = s / d;
+        }
+
+        let scale = (T::from(2).unwrap() - w) / w;
+        Matrix::from_vec_col(x.into_iter().map(|x| x * scale).collect())
+    }
+}
@@ -1,6 +1,17 @@
-use crate::Matrix;
+pub mod cholesky;
+pub mod coo;
+pub mod csc;
+pub mod lu;
+pub mod ordering;
+
+use crate::{error::MatrixError, Matrix};
 use num_traits::identities::Zero;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "rayon")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SparseMatrix<T> {
     pub(crate) rows: usize,
@@ -10,6 +21,274 @@ pub struct SparseMatrix<T> {
     pub(crate) row_ptr: Vec<usize>,
 }
 
+impl<T> SparseMatrix<T> {
+    pub fn from_csr(
+        rows: usize,
+        cols: usize,
+        row_ptr: Vec<usize>,
+        col_idx: Vec<usize>,
+        val: Vec<T>,
+    ) -> Self {
+        Self::try_from_csr(rows, cols, row_ptr, col_idx, val).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // Column indices have to be strictly increasing within each row; merging, the
+    // incomplete factorizations and the orderings all rely on it.
+    pub fn try_from_csr(
+        rows: usize,
+        cols: usize,
+        row_ptr: Vec<usize>,
+        col_idx: Vec<usize>,
+        val: Vec<T>,
+    ) -> Result<Self, MatrixError> {
+        let invalid = |reason| Err(MatrixError::InvalidSparse { reason });
+        if row_ptr.len() != rows + 1 {
+            return invalid("row_ptr must have rows + 1 entries.");
+        }
+        if row_ptr[0] != 0 {
+            return invalid("row_ptr must start with 0.");
+        }
+        if row_ptr.windows(2).any(|w| w[0] > w[1]) {
+            return invalid("row_ptr must be non-decreasing.");
+        }
+        if col_idx.len() != val.len() || row_ptr[rows] != val.len() {
+            return invalid("row_ptr, col_idx and val disagree on the number of entries.");
+        }
+        if col_idx.iter().any(|&c| c >= cols) {
+            return invalid("Column index out of bounds.");
+        }
+        if row_ptr
+            .windows(2)
+            .any(|w| col_idx[w[0]..w[1]].windows(2).any(|c| c[0] >= c[1]))
+        {
+            return invalid("Column indices must be sorted and unique within each row.");
+        }
+        Ok(Self {
+            rows,
+            cols,
+            val,
+            col_idx,
+            row_ptr,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.val.len()
+    }
+
+    pub(crate) fn check_square(&self) -> Result<(), MatrixError> {
+        if self.rows != self.cols {
+            return Err(MatrixError::NotSquare {
+                shape: (self.rows, self.cols),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn row_ptr(&self) -> &[usize] {
+        &self.row_ptr
+    }
+
+    pub fn col_idx(&self) -> &[usize] {
+        &self.col_idx
+    }
+
+    pub fn val(&self) -> &[T] {
+        &self.val
+    }
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Clone,
+{
+    // B(i, j) = A(rows[i], cols[j])
+    pub fn permute(&self, rows: &[usize], cols: &[usize]) -> Self {
+        check_perm(rows, self.rows);
+        check_perm(cols, self.cols);
+        let mut cinv = vec![usize::MAX; self.cols];
+        for (new, &old) in cols.iter().enumerate() {
+            cinv[old] = new;
+        }
+
+        let mut row_ptr = Vec::with_capacity(self.rows + 1);
+        let mut col_idx = Vec::with_capacity(self.val.len());
+        let mut val = Vec::with_capacity(self.val.len());
+        row_ptr.push(0);
+        for &r in rows.iter() {
+            let mut row: Vec<_> = (self.row_ptr[r]..self.row_ptr[r + 1])
+                .map(|n| (cinv[self.col_idx[n]], n))
+                .collect();
+            row.sort_unstable_by_key(|&(c, _)| c);
+            for (c, n) in row {
+                col_idx.push(c);
+                val.push(self.val[n].clone());
+            }
+            row_ptr.push(col_idx.len());
+        }
+
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            val,
+            col_idx,
+            row_ptr,
+        }
+    }
+}
+
+// Scatters the CSR arrays into their transpose in O(nnz + rows + cols).
+// Orderings index old positions by new ones; a repeated or out of range entry would
+// silently drop rows or columns.
+pub(crate) fn check_perm(perm: &[usize], n: usize) {
+    let mut seen = vec![false; n];
+    let valid = perm.len() == n
+        && perm
+            .iter()
+            .all(|&p| p < n && !std::mem::replace(&mut seen[p], true));
+    assert!(valid, "The ordering must be a permutation of 0..{}.", n);
+}
+
+pub(crate) fn transpose_compressed<T>(
+    rows: usize,
+    cols: usize,
+    ptr: &[usize],
+    idx: &[usize],
+    val: &[T],
+) -> (Vec<usize>, Vec<usize>, Vec<T>)
+where
+    T: Clone,
+{
+    let mut t_ptr = vec![0; cols + 1];
+    for &c in idx.iter() {
+        t_ptr[c + 1] += 1;
+    }
+    for c in 0..cols {
+        t_ptr[c + 1] += t_ptr[c];
+    }
+
+    let mut next = t_ptr.clone();
+    let mut t_idx = vec![0; idx.len()];
+    let mut t_val: Vec<Option<T>> = vec![None; idx.len()];
+    for r in 0..rows {
+        for n in ptr[r]..ptr[r + 1] {
+            let c = idx[n];
+            t_idx[next[c]] = r;
+            t_val[next[c]] = Some(val[n].clone());
+            next[c] += 1;
+        }
+    }
+    (
+        t_ptr,
+        t_idx,
+        t_val.into_iter().map(Option::unwrap).collect(),
+    )
+}
+
+// Splits the rows into at most `parts` contiguous ranges holding about the same number
+// of nonzeros. Consecutive entries of the result are the bounds of one range.
+#[cfg(feature = "rayon")]
+pub(crate) fn balanced_rows(row_ptr: &[usize], parts: usize) -> Vec<usize> {
+    let rows = row_ptr.len() - 1;
+    let nnz = row_ptr[rows];
+    let mut bounds = vec![0];
+    for p in 1..parts {
+        let r = row_ptr.partition_point(|&x| x < nnz * p / parts).min(rows);
+        if r > *bounds.last().unwrap() {
+            bounds.push(r);
+        }
+    }
+    if rows > *bounds.last().unwrap() {
+        bounds.push(rows);
+    }
+    bounds
+}
+
+// Cuts a row-major buffer of the given row width along the bounds from balanced_rows.
+#[cfg(feature = "rayon")]
+pub(crate) fn split_rows<'a, T>(
+    mut array: &'a mut [T],
+    bounds: &[usize],
+    width: usize,
+) -> Vec<&'a mut [T]> {
+    let mut ret = Vec::with_capacity(bounds.len());
+    for w in bounds.windows(2) {
+        let (head, tail) = array.split_at_mut((w[1] - w[0]) * width);
+        ret.push(head);
+        array = tail;
+    }
+    ret
+}
+
+// Walks two rows with sorted column indices and combines the matching entries.
+// f receives None for a side that has no entry in that column; zero results are dropped.
+pub(crate) fn zip_sparse<T, F>(
+    lhs: &SparseMatrix<T>,
+    rhs: &SparseMatrix<T>,
+    f: F,
+) -> SparseMatrix<T>
+where
+    T: Zero + Copy,
+    F: Fn(Option<T>, Option<T>) -> Option<T>,
+{
+    assert_eq!(lhs.rows, rhs.rows);
+    assert_eq!(lhs.cols, rhs.cols);
+
+    let mut row_ptr = Vec::with_capacity(lhs.rows + 1);
+    let mut col_idx = Vec::with_capacity(lhs.val.len().max(rhs.val.len()));
+    let mut val = Vec::with_capacity(lhs.val.len().max(rhs.val.len()));
+    row_ptr.push(0);
+
+    for r in 0..lhs.rows {
+        let (mut i, end_i) = (lhs.row_ptr[r], lhs.row_ptr[r + 1]);
+        let (mut j, end_j) = (rhs.row_ptr[r], rhs.row_ptr[r + 1]);
+        while i < end_i || j < end_j {
+            let ci = if i < end_i {
+                lhs.col_idx[i]
+            } else {
+                usize::MAX
+            };
+            let cj = if j < end_j {
+                rhs.col_idx[j]
+            } else {
+                usize::MAX
+            };
+            let (c, x) = if ci < cj {
+                i += 1;
+                (ci, f(Some(lhs.val[i - 1]), None))
+            } else if cj < ci {
+                j += 1;
+                (cj, f(None, Some(rhs.val[j - 1])))
+            } else {
+                i += 1;
+                j += 1;
+                (ci, f(Some(lhs.val[i - 1]), Some(rhs.val[j - 1])))
+            };
+            if let Some(x) = x.filter(|x| !x.is_zero()) {
+                col_idx.push(c);
+                val.push(x);
+            }
+        }
+        row_ptr.push(col_idx.len());
+    }
+
+    SparseMatrix {
+        rows: lhs.rows,
+        cols: lhs.cols,
+        val,
+        col_idx,
+        row_ptr,
+    }
+}
+
 impl<T> SparseMatrix<T>
 where
     T: Zero + Clone,
@@ -41,20 +320,98 @@ where
         }
     }
 
-    pub fn transpose(&self) -> Self {
-        self.to_dence().transpose().to_sparse()
-    }
-
     pub fn to_dence(&self) -> Matrix<T> {
         let mut mat = Matrix::zero(self.rows, self.cols);
 
-        let mut i = 0;
-        for n in 0..self.val.len() {
-            if n >= self.row_ptr[i + 1] {
-                i += 1;
+        for i in 0..self.rows {
+            for n in self.row_ptr[i]..self.row_ptr[i + 1] {
+                mat.array[i * self.cols + self.col_idx[n]] = self.val[n].clone();
             }
-            mat.array[i * self.rows + self.col_idx[n]] = self.val[n].clone();
         }
         mat
     }
 }
+
+#[cfg(not(feature = "rayon"))]
+impl<T> SparseMatrix<T>
+where
+    T: Clone,
+{
+    pub fn transpose(&self) -> Self {
+        let (row_ptr, col_idx, val) = transpose_compressed(
+            self.rows,
+            self.cols,
+            &self.row_ptr,
+            &self.col_idx,
+            &self.val,
+        );
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            val,
+            col_idx,
+            row_ptr,
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> SparseMatrix<T>
+where
+    T: Clone + Send + Sync,
+{
+    // One shared histogram gives the row pointers of the result. Its rows are then
+    // split into nnz-balanced ranges of input columns, and each task scatters only the
+    // entries falling into its range, found by binary search in every sorted input row.
+    pub fn transpose(&self) -> Self {
+        let counts: Vec<AtomicUsize> = (0..self.cols).map(|_| AtomicUsize::new(0)).collect();
+        self.col_idx.par_iter().for_each(|&c| {
+            counts[c].fetch_add(1, Ordering::Relaxed);
+        });
+        let mut row_ptr = vec![0; self.cols + 1];
+        for (c, n) in counts.into_iter().enumerate() {
+            row_ptr[c + 1] = row_ptr[c] + n.into_inner();
+        }
+
+        let bounds = balanced_rows(&row_ptr, num_cpus::get());
+        let blocks: Vec<(Vec<usize>, Vec<T>)> = bounds
+            .par_windows(2)
+            .map(|w| {
+                let (c0, c1) = (w[0], w[1]);
+                let base = row_ptr[c0];
+                let mut next: Vec<_> = row_ptr[c0..c1].iter().map(|&p| p - base).collect();
+                let len = row_ptr[c1] - base;
+                let mut idx = vec![0; len];
+                let mut val: Vec<Option<T>> = vec![None; len];
+                for r in 0..self.rows {
+                    let (lo, hi) = (self.row_ptr[r], self.row_ptr[r + 1]);
+                    let row = &self.col_idx[lo..hi];
+                    let start = lo + row.partition_point(|&c| c < c0);
+                    let end = lo + row.partition_point(|&c| c < c1);
+                    for n in start..end {
+                        let c = self.col_idx[n] - c0;
+                        idx[next[c]] = r;
+                        val[next[c]] = Some(self.val[n].clone());
+                        next[c] += 1;
+                    }
+                }
+                (idx, val.into_iter().map(Option::unwrap).collect())
+            })
+            .collect();
+
+        let mut col_idx = Vec::with_capacity(self.val.len());
+        let mut val = Vec::with_capacity(self.val.len());
+        for (i, v) in blocks {
+            col_idx.extend(i);
+            val.extend(v);
+        }
+
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            val,
+            col_idx,
+            row_ptr,
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    linalg::LinalgError,
+    sparse::{check_perm, csc::CscMatrix, transpose_compressed, SparseMatrix},
+    Matrix,
+};
+use num_traits::Float;
+
+// Symbolic analysis of PAP^T = LL^T: elimination tree and the row patterns of L.
+// It only depends on the sparsity pattern of A and the ordering, so it can be
+// reused for every matrix with the same pattern. The ordering defaults to
+// approximate minimum degree.
+#[derive(Clone, Debug)]
+pub struct CholeskySymbolic {
+    n: usize,
+    perm: Vec<usize>,
+    parent: Vec<usize>,
+    // pattern of the permuted input, used to check that a refactorization matches
+    a_ptr: Vec<usize>,
+    a_idx: Vec<usize>,
+    // row patterns of L without the diagonal, each in topological order
+    row_ptr: Vec<usize>,
+    row_idx: Vec<usize>,
+    // column patterns of L, diagonal first and then increasing rows
+    col_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+}
+
+impl CholeskySymbolic {
+    pub fn new<T>(a: &SparseMatrix<T>, perm: Option<&[usize]>) -> Self
+    where
+        T: Clone,
+    {
+        assert_eq!(a.rows, a.cols, "The matrix must be square.");
+        let n = a.rows;
+        let perm = match perm {
+            Some(p) => {
+                check_perm(p, n);
+                p.to_vec()
+            }
+            None => a.amd(),
+        };
+        let c = a.permute(&perm, &perm);
+        let parent = etree(&c);
+
+        let mut mark = vec![usize::MAX; n];
+        let mut stack = Vec::new();
+        let mut row_ptr = vec![0];
+        let mut row_idx = Vec::new();
+        let mut counts = vec![1; n];
+        for k in 0..n {
+            let start = row_idx.len();
+            ereach(&c, k, &parent, &mut mark, &mut stack, &mut row_idx);
+            for &i in row_idx[start..].iter() {
+                counts[i] += 1;
+            }
+            row_ptr.push(row_idx.len());
+        }
+        let mut col_ptr = vec![0; n + 1];
+        for j in 0..n {
+            col_ptr[j + 1] = col_ptr[j] + counts[j];
+        }
+        // row k enters the columns of its pattern in increasing k, so each column ends up sorted
+        let mut col_idx = vec![0; col_ptr[n]];
+        let mut next = col_ptr[..n].to_vec();
+        for k in 0..n {
+            for &i in row_idx[row_ptr[k]..row_ptr[k + 1]].iter() {
+                col_idx[next[i]] = k;
+                next[i] += 1;
+            }
+            col_idx[next[k]] = k;
+            next[k] += 1;
+        }
+
+        Self {
+            n,
+            perm,
+            parent,
+            a_ptr: c.row_ptr,
+            a_idx: c.col_idx,
+            row_ptr,
+            row_idx,
+            col_ptr,
+            col_idx,
+        }
+    }
+
+    pub fn perm(&self) -> &[usize] {
+        &self.perm
+    }
+
+    pub fn etree(&self) -> &[usize] {
+        &self.parent
+    }
+
+    pub fn nnz_l(&self) -> usize {
+        self.col_ptr[self.n]
+    }
+
+    // entries in each column and each row of L, diagonal included
+    pub fn col_counts(&self) -> Vec<usize> {
+        self.col_ptr.windows(2).map(|w| w[1] - w[0]).collect()
+    }
+
+    pub fn row_counts(&self) -> Vec<usize> {
+        self.row_ptr.windows(2).map(|w| w[1] - w[0] + 1).collect()
+    }
+}
+
+// parent[i] == usize::MAX marks a root
+fn etree<T>(c: &SparseMatrix<T>) -> Vec<usize> {
+    let n = c.rows;
+    let mut parent = vec![usize::MAX; n];
+    let mut ancestor = vec![usize::MAX; n];
+    for k in 0..n {
+        for p in c.row_ptr[k]..c.row_ptr[k + 1] {
+            let mut i = c.col_idx[p];
+            while i != usize::MAX && i < k {
+                let next = ancestor[i];
+                ancestor[i] = k;
+                if next == usize::MAX {
+                    parent[i] = k;
+                }
+                i = next;
+            }
+        }
+    }
+    parent
+}
+
+// Appends the pattern of row k of L (without the diagonal) to out in topological order.
+fn ereach<T>(
+    c: &SparseMatrix<T>,
+    k: usize,
+    parent: &[usize],
+    mark: &mut [usize],
+    stack: &mut Vec<usize>,
+    out: &mut Vec<usize>,
+) {
+    let start = out.len();
+    mark[k] = k;
+    for p in c.row_ptr[k]..c.row_ptr[k + 1] {
+        let mut i = c.col_idx[p];
+        if i > k {
+            continue;
+        }
+        stack.clear();
+        while mark[i] != k {
+            stack.push(i);
+            mark[i] = k;
+            i = parent[i];
+        }
+        // the path is pushed in reverse so that the final reversal restores it
+        out.extend(stack.iter().rev());
+    }
+    out[start..].reverse();
+}
+
+// Left-looking sparse Cholesky factorization PAP^T = LL^T. Only the lower triangle of A is read.
+#[derive(Clone, Debug)]
+pub struct SparseCholesky<T> {
+    symbolic: CholeskySymbolic,
+    l: CscMatrix<T>,
+}
+
+impl<T> SparseCholesky<T>
+where
+    T: Float,
+{
+    pub fn new(a: &SparseMatrix<T>, perm: Option<&[usize]>) -> Result<Self, LinalgError> {
+        a.check_square()?;
+        Self::factorize(&CholeskySymbolic::new(a, perm), a)
+    }
+
+    pub fn factorize(
+        symbolic: &CholeskySymbolic,
+        a: &SparseMatrix<T>,
+    ) -> Result<Self, LinalgError> {
+        let l = numeric(symbolic, a)?;
+        Ok(Self {
+            symbolic: symbolic.clone(),
+            l,
+        })
+    }
+
+    pub fn refactorize(&mut self, a: &SparseMatrix<T>) -> Result<(), LinalgError> {
+        self.l = numeric(&self.symbolic, a)?;
+        Ok(())
+    }
+
+    pub fn symbolic(&self) -> &CholeskySymbolic {
+        &self.symbolic
+    }
+
+    pub fn l(&self) -> &CscMatrix<T> {
+        &self.l
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Matrix<T> {
+        let n = self.symbolic.n;
+        assert_eq!(b.rows, n, "The right-hand side must have {} rows.", n);
+        let l = &self.l;
+        let perm = &self.symbolic.perm;
+        let mut ret = Matrix::zero(n, b.cols);
+        let mut x = vec![T::zero(); n];
+
+        for c in 0..b.cols {
+            for (i, &p) in perm.iter().enumerate() {
+                x[i] = b.array[p * b.cols + c];
+            }
+            for j in 0..n {
+                x[j] = x[j] / l.val[l.col_ptr[j]];
+                for p in (l.col_ptr[j] + 1)..l.col_ptr[j + 1] {
+                    x[l.row_idx[p]] = x[l.row_idx[p]] - l.val[p] * x[j];
+                }
+            }
+            for j in (0..n).rev() {
+                for p in (l.col_ptr[j] + 1)..l.col_ptr[j + 1] {
+                    x[j] = x[j] - l.val[p] * x[l.row_idx[p]];
+                }
+                x[j] = x[j] / l.val[l.col_ptr[j]];
+            }
+            for (i, &p) in perm.iter().enumerate() {
+                ret.array[p * b.cols + c] = x[i];
+            }
+        }
+        ret
+    }
+}
+
+// Column j of L is column j of A updated by every column k < j with L(j, k) != 0, which
+// is exactly the row pattern of row j from the symbolic analysis.
+fn numeric<T>(s: &CholeskySymbolic, a: &SparseMatrix<T>) -> Result<CscMatrix<T>, LinalgError>
+where
+    T: Float,
+{
+    let n = s.n;
+    if a.rows != n || a.cols != n {
+        return Err(LinalgError::PatternMismatch);
+    }
+    let c = a.permute(&s.perm, &s.perm);
+    if c.row_ptr != s.a_ptr || c.col_idx != s.a_idx {
+        return Err(LinalgError::PatternMismatch);
+    }
+    let (c_ptr, c_idx, c_val) = transpose_compressed(n, n, &c.row_ptr, &c.col_idx, &c.val);
+
+    let (col_ptr, row_idx) = (&s.col_ptr, &s.col_idx);
+    let mut val = vec![T::zero(); row_idx.len()];
+    // next[k] is the first entry of column k at or below the current row
+    let mut next: Vec<_> = col_ptr[..n].iter().map(|&p| p + 1).collect();
+    let mut x = vec![T::zero(); n];
+
+    for j in 0..n {
+        for p in c_ptr[j]..c_ptr[j + 1] {
+            if c_idx[p] >= j {
+                x[c_idx[p]] = c_val[p];
+            }
+        }
+        for &k in s.row_idx[s.row_ptr[j]..s.row_ptr[j + 1]].iter() {
+            let ljk = val[next[k]];
+            for p in next[k]..col_ptr[k + 1] {
+                x[row_idx[p]] = x[row_idx[p]] - val[p] * ljk;
+            }
+            next[k] += 1;
+        }
+
+        let d = x[j];
+        x[j] = T::zero();
+        if d <= T::zero() || d.is_nan() {
+            return Err(LinalgError::NotPositiveDefinite);
+        }
+        let d = d.sqrt();
+        val[col_ptr[j]] = d;
+        for p in (col_ptr[j] + 1)..col_ptr[j + 1] {
+            val[p] = x[row_idx[p]] / d;
+            x[row_idx[p]] = T::zero();
+        }
+    }
+
+    Ok(CscMatrix {
+        rows: n,
+        cols: n,
+        val,
+        row_idx: row_idx.clone(),
+        col_ptr: col_ptr.clone(),
+    })
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Float,
+{
+    pub fn cholesky(&self, perm: Option<&[usize]>) -> Result<SparseCholesky<T>, LinalgError> {
+        SparseCholesky::new(self, perm)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::sparse::{csc::CscMatrix, SparseMatrix};
+use num_traits::identities::Zero;
+
+// Triplet builder. Duplicate entries are summed on conversion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CooMatrix<T> {
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+    pub(crate) row: Vec<usize>,
+    pub(crate) col: Vec<usize>,
+    pub(crate) val: Vec<T>,
+}
+
+impl<T> CooMatrix<T> {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self::with_capacity(rows, cols, 0)
+    }
+
+    pub fn with_capacity(rows: usize, cols: usize, capacity: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            row: Vec::with_capacity(capacity),
+            col: Vec::with_capacity(capacity),
+            val: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, row: usize, col: usize, val: T) {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) is out of bounds for ({}, {})",
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        self.row.push(row);
+        self.col.push(col);
+        self.val.push(val);
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.val.len()
+    }
+}
+
+impl<T> CooMatrix<T>
+where
+    T: Zero + Clone,
+{
+    pub fn to_csr(&self) -> SparseMatrix<T> {
+        let mut ptr = vec![0; self.rows + 1];
+        for &r in self.row.iter() {
+            ptr[r + 1] += 1;
+        }
+        for r in 0..self.rows {
+            ptr[r + 1] += ptr[r];
+        }
+
+        let mut next = ptr.clone();
+        let mut entries: Vec<Option<(usize, T)>> = vec![None; self.val.len()];
+        for ((&r, &c), v) in self.row.iter().zip(self.col.iter()).zip(self.val.iter()) {
+            entries[next[r]] = Some((c, v.clone()));
+            next[r] += 1;
+        }
+        let mut entries: Vec<_> = entries.into_iter().map(Option::unwrap).collect();
+
+        let mut row_ptr = Vec::with_capacity(self.rows + 1);
+        let mut col_idx: Vec<usize> = Vec::with_capacity(entries.len());
+        let mut val: Vec<T> = Vec::with_capacity(entries.len());
+        row_ptr.push(0);
+        for r in 0..self.rows {
+            let row = &mut entries[ptr[r]..ptr[r + 1]];
+            row.sort_by_key(|(c, _)| *c);
+            let start = col_idx.len();
+            for (c, v) in row.iter() {
+                if col_idx.len() > start && col_idx.last() == Some(c) {
+                    let last = val.len() - 1;
+                    val[last] = val[last].clone() + v.clone();
+                } else {
+                    col_idx.push(*c);
+                    val.push(v.clone());
+                }
+            }
+            row_ptr.push(col_idx.len());
+        }
+
+        SparseMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            val,
+            col_idx,
+            row_ptr,
+        }
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        self.to_csr().to_csc()
+    }
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Clone,
+{
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        let mut coo = CooMatrix::with_capacity(self.rows, self.cols, self.val.len());
+        for r in 0..self.rows {
+            for n in self.row_ptr[r]..self.row_ptr[r + 1] {
+                coo.push(r, self.col_idx[n], self.val[n].clone());
+            }
+        }
+        coo
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    error::MatrixError,
+    sparse::{transpose_compressed, SparseMatrix},
+    Matrix,
+};
+use num_traits::identities::Zero;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T> {
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+    pub(crate) val: Vec<T>,
+    pub(crate) row_idx: Vec<usize>,
+    pub(crate) col_ptr: Vec<usize>,
+}
+
+impl<T> CscMatrix<T> {
+    pub fn from_csc(
+        rows: usize,
+        cols: usize,
+        col_ptr: Vec<usize>,
+        row_idx: Vec<usize>,
+        val: Vec<T>,
+    ) -> Self {
+        Self::try_from_csc(rows, cols, col_ptr, row_idx, val).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_csc(
+        rows: usize,
+        cols: usize,
+        col_ptr: Vec<usize>,
+        row_idx: Vec<usize>,
+        val: Vec<T>,
+    ) -> Result<Self, MatrixError> {
+        SparseMatrix::try_from_csr(cols, rows, col_ptr, row_idx, val).map(|m| m.transpose_as_csc())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.val.len()
+    }
+
+    pub fn col_ptr(&self) -> &[usize] {
+        &self.col_ptr
+    }
+
+    pub fn row_idx(&self) -> &[usize] {
+        &self.row_idx
+    }
+
+    pub fn val(&self) -> &[T] {
+        &self.val
+    }
+
+    // the CSC arrays of A are the CSR arrays of A^T
+    pub fn transpose_as_csr(self) -> SparseMatrix<T> {
+        SparseMatrix {
+            rows: self.cols,
+            cols: self.rows,
+            val: self.val,
+            col_idx: self.row_idx,
+            row_ptr: self.col_ptr,
+        }
+    }
+}
+
+impl<T> SparseMatrix<T> {
+    pub fn transpose_as_csc(self) -> CscMatrix<T> {
+        CscMatrix {
+            rows: self.cols,
+            cols: self.rows,
+            val: self.val,
+            row_idx: self.col_idx,
+            col_ptr: self.row_ptr,
+        }
+    }
+}
+
+impl<T> CscMatrix<T>
+where
+    T: Zero + Clone,
+{
+    pub fn to_csr(&self) -> SparseMatrix<T> {
+        let (row_ptr, col_idx, val) = transpose_compressed(
+            self.cols,
+            self.rows,
+            &self.col_ptr,
+            &self.row_idx,
+            &self.val,
+        );
+        SparseMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            val,
+            col_idx,
+            row_ptr,
+        }
+    }
+
+    pub fn to_dence(&self) -> Matrix<T> {
+        let mut mat = Matrix::zero(self.rows, self.cols);
+        for j in 0..self.cols {
+            for n in self.col_ptr[j]..self.col_ptr[j + 1] {
+                mat.array[self.row_idx[n] * self.cols + j] = self.val[n].clone();
+            }
+        }
+        mat
+    }
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Zero + Clone,
+{
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        let (col_ptr, row_idx, val) = transpose_compressed(
+            self.rows,
+            self.cols,
+            &self.row_ptr,
+            &self.col_idx,
+            &self.val,
+        );
+        CscMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            val,
+            row_idx,
+            col_ptr,
+        }
+    }
+}
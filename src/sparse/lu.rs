@@ -0,0 +1,434 @@
+use crate::{
+    linalg::LinalgError,
+    sparse::{
+        check_perm, cholesky::CholeskySymbolic, csc::CscMatrix, transpose_compressed, SparseMatrix,
+    },
+    Matrix,
+};
+use num_traits::Float;
+
+// Symbolic analysis for the sparse LU factorization AQ = P^T LU. The column ordering
+// defaults to minimum degree on A^T A, the usual stand-in for COLAMD. The Cholesky
+// factor R of Q^T A^T A Q bounds the factors for every choice of row pivots (George
+// and Ng): the pattern of U lies in R and that of L in R^T. Its elimination tree is the
+// column elimination tree of AQ and its row and column counts bound the columns of U
+// and L, which the numeric phase uses to size the factors up front.
+#[derive(Clone, Debug)]
+pub struct LuSymbolic {
+    n: usize,
+    q: Vec<usize>,
+    ata: CholeskySymbolic,
+    // pattern of A in CSC, used to check that a refactorization matches
+    a_ptr: Vec<usize>,
+    a_idx: Vec<usize>,
+    l_counts: Vec<usize>,
+    u_counts: Vec<usize>,
+}
+
+impl LuSymbolic {
+    pub fn new<T>(a: &SparseMatrix<T>, q: Option<&[usize]>) -> Self {
+        assert_eq!(a.rows, a.cols, "The matrix must be square.");
+        let n = a.rows;
+        let (a_ptr, a_idx, _) =
+            transpose_compressed(n, n, &a.row_ptr, &a.col_idx, &vec![(); a.col_idx.len()]);
+        let ata = ata_pattern(a, &a_ptr, &a_idx);
+        let q = match q {
+            Some(q) => {
+                check_perm(q, n);
+                q.to_vec()
+            }
+            None => ata.amd(),
+        };
+
+        let chol = CholeskySymbolic::new(&ata, Some(&q));
+        let l_counts = chol.col_counts();
+        let u_counts = chol.row_counts();
+        Self {
+            n,
+            q,
+            ata: chol,
+            a_ptr,
+            a_idx,
+            l_counts,
+            u_counts,
+        }
+    }
+
+    pub fn col_perm(&self) -> &[usize] {
+        &self.q
+    }
+
+    // column elimination tree of AQ; usize::MAX marks a root
+    pub fn etree(&self) -> &[usize] {
+        self.ata.etree()
+    }
+
+    // upper bounds on the number of entries in each column of L and U
+    pub fn l_counts(&self) -> &[usize] {
+        &self.l_counts
+    }
+
+    pub fn u_counts(&self) -> &[usize] {
+        &self.u_counts
+    }
+
+    pub fn lnz(&self) -> usize {
+        self.l_counts.iter().sum()
+    }
+
+    pub fn unz(&self) -> usize {
+        self.u_counts.iter().sum()
+    }
+}
+
+// Pattern of A^T A with the full diagonal, given the columns of A.
+fn ata_pattern<T>(a: &SparseMatrix<T>, a_ptr: &[usize], a_idx: &[usize]) -> SparseMatrix<()> {
+    let n = a.cols;
+    let mut mark = vec![usize::MAX; n];
+    let mut row_ptr = vec![0];
+    let mut col_idx = Vec::new();
+    for j in 0..n {
+        let start = col_idx.len();
+        mark[j] = j;
+        col_idx.push(j);
+        for &r in a_idx[a_ptr[j]..a_ptr[j + 1]].iter() {
+            for &i in a.col_idx[a.row_ptr[r]..a.row_ptr[r + 1]].iter() {
+                if mark[i] != j {
+                    mark[i] = j;
+                    col_idx.push(i);
+                }
+            }
+        }
+        col_idx[start..].sort_unstable();
+        row_ptr.push(col_idx.len());
+    }
+    SparseMatrix {
+        rows: n,
+        cols: n,
+        val: vec![(); col_idx.len()],
+        col_idx,
+        row_ptr,
+    }
+}
+
+// Left-looking (Gilbert-Peierls) sparse LU factorization PAQ = LU with threshold
+// partial pivoting. The diagonal is kept as pivot while |a_jj| >= tol * max_i |a_ij|,
+// so tol = 1 is ordinary partial pivoting.
+#[derive(Clone, Debug)]
+pub struct SparseLu<T> {
+    symbolic: LuSymbolic,
+    tol: T,
+    l: CscMatrix<T>,
+    u: CscMatrix<T>,
+    pinv: Vec<usize>,
+}
+
+impl<T> SparseLu<T>
+where
+    T: Float,
+{
+    pub fn new(a: &SparseMatrix<T>, q: Option<&[usize]>, tol: T) -> Result<Self, LinalgError> {
+        a.check_square()?;
+        Self::factorize(&LuSymbolic::new(a, q), a, tol)
+    }
+
+    pub fn factorize(
+        symbolic: &LuSymbolic,
+        a: &SparseMatrix<T>,
+        tol: T,
+    ) -> Result<Self, LinalgError> {
+        assert!(
+            tol > T::zero() && tol <= T::one(),
+            "The pivot tolerance must be in (0, 1]."
+        );
+        let (l, u, pinv) = numeric(symbolic, a, tol)?;
+        Ok(Self {
+            symbolic: symbolic.clone(),
+            tol,
+            l,
+            u,
+            pinv,
+        })
+    }
+
+    pub fn refactorize(&mut self, a: &SparseMatrix<T>) -> Result<(), LinalgError> {
+        let (l, u, pinv) = numeric(&self.symbolic, a, self.tol)?;
+        self.l = l;
+        self.u = u;
+        self.pinv = pinv;
+        Ok(())
+    }
+
+    pub fn symbolic(&self) -> &LuSymbolic {
+        &self.symbolic
+    }
+
+    pub fn l(&self) -> &CscMatrix<T> {
+        &self.l
+    }
+
+    pub fn u(&self) -> &CscMatrix<T> {
+        &self.u
+    }
+
+    // row i of PA is row perm[i] of A
+    pub fn row_perm(&self) -> Vec<usize> {
+        let mut p = vec![0; self.pinv.len()];
+        for (i, &k) in self.pinv.iter().enumerate() {
+            p[k] = i;
+        }
+        p
+    }
+
+    pub fn col_perm(&self) -> &[usize] {
+        &self.symbolic.q
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Matrix<T> {
+        let n = self.symbolic.n;
+        assert_eq!(b.rows, n, "The right-hand side must have {} rows.", n);
+        let (l, u) = (&self.l, &self.u);
+        let mut ret = Matrix::zero(n, b.cols);
+        let mut x = vec![T::zero(); n];
+
+        for c in 0..b.cols {
+            for (i, &k) in self.pinv.iter().enumerate() {
+                x[k] = b.array[i * b.cols + c];
+            }
+            // L has a unit diagonal stored first in each column
+            for j in 0..n {
+                for p in (l.col_ptr[j] + 1)..l.col_ptr[j + 1] {
+                    x[l.row_idx[p]] = x[l.row_idx[p]] - l.val[p] * x[j];
+                }
+            }
+            // U has its diagonal stored last in each column
+            for j in (0..n).rev() {
+                let last = u.col_ptr[j + 1] - 1;
+                x[j] = x[j] / u.val[last];
+                for p in u.col_ptr[j]..last {
+                    x[u.row_idx[p]] = x[u.row_idx[p]] - u.val[p] * x[j];
+                }
+            }
+            for (k, &j) in self.symbolic.q.iter().enumerate() {
+                ret.array[j * b.cols + c] = x[k];
+            }
+        }
+        ret
+    }
+}
+
+type Factors<T> = (CscMatrix<T>, CscMatrix<T>, Vec<usize>);
+
+fn numeric<T>(s: &LuSymbolic, a: &SparseMatrix<T>, tol: T) -> Result<Factors<T>, LinalgError>
+where
+    T: Float,
+{
+    let n = s.n;
+    if a.rows != n || a.cols != n {
+        return Err(LinalgError::PatternMismatch);
+    }
+    let a = a.to_csc();
+    if a.col_ptr != s.a_ptr || a.row_idx != s.a_idx {
+        return Err(LinalgError::PatternMismatch);
+    }
+
+    let (lnz, unz) = (s.lnz(), s.unz());
+    let mut l_ptr = Vec::with_capacity(n + 1);
+    let mut l_idx = Vec::with_capacity(lnz);
+    let mut l_val = Vec::with_capacity(lnz);
+    let mut u_ptr = Vec::with_capacity(n + 1);
+    let mut u_idx = Vec::with_capacity(unz);
+    let mut u_val = Vec::with_capacity(unz);
+    let mut pinv = vec![usize::MAX; n];
+
+    let mut x = vec![T::zero(); n];
+    let mut mark = vec![false; n];
+    let mut pattern = Vec::with_capacity(n);
+    let mut stack = Vec::with_capacity(n);
+
+    for k in 0..n {
+        l_ptr.push(l_idx.len());
+        u_ptr.push(u_idx.len());
+        let col = s.q[k];
+        let (lo, hi) = (a.col_ptr[col], a.col_ptr[col + 1]);
+
+        // x = L \ A(:, col), restricted to the rows reachable from the pattern of A(:, col)
+        reach(
+            &l_ptr,
+            &l_idx,
+            &pinv,
+            &a.row_idx[lo..hi],
+            &mut mark,
+            &mut stack,
+            &mut pattern,
+        );
+        for &i in pattern.iter() {
+            x[i] = T::zero();
+        }
+        for p in lo..hi {
+            x[a.row_idx[p]] = a.val[p];
+        }
+        for &j in pattern.iter() {
+            let jj = pinv[j];
+            if jj == usize::MAX {
+                continue;
+            }
+            let end = if jj + 1 < l_ptr.len() {
+                l_ptr[jj + 1]
+            } else {
+                l_idx.len()
+            };
+            for p in (l_ptr[jj] + 1)..end {
+                x[l_idx[p]] = x[l_idx[p]] - l_val[p] * x[j];
+            }
+        }
+
+        let mut ipiv = usize::MAX;
+        let mut amax = -T::one();
+        for &i in pattern.iter() {
+            if pinv[i] == usize::MAX {
+                if x[i].abs() > amax {
+                    amax = x[i].abs();
+                    ipiv = i;
+                }
+            } else {
+                u_idx.push(pinv[i]);
+                u_val.push(x[i]);
+            }
+        }
+        if ipiv == usize::MAX || amax <= T::zero() || amax.is_nan() {
+            return Err(LinalgError::Singular);
+        }
+        if pinv[col] == usize::MAX && x[col].abs() >= amax * tol {
+            ipiv = col;
+        }
+
+        let pivot = x[ipiv];
+        u_idx.push(k);
+        u_val.push(pivot);
+        pinv[ipiv] = k;
+        l_idx.push(ipiv);
+        l_val.push(T::one());
+        for &i in pattern.iter() {
+            if pinv[i] == usize::MAX {
+                l_idx.push(i);
+                l_val.push(x[i] / pivot);
+            }
+            x[i] = T::zero();
+        }
+    }
+    l_ptr.push(l_idx.len());
+    u_ptr.push(u_idx.len());
+
+    for i in l_idx.iter_mut() {
+        *i = pinv[*i];
+    }
+    // the unit diagonal of L stays first and the diagonal of U stays last
+    sort_columns(&l_ptr, &mut l_idx, &mut l_val);
+    sort_columns(&u_ptr, &mut u_idx, &mut u_val);
+
+    let l = CscMatrix {
+        rows: n,
+        cols: n,
+        val: l_val,
+        row_idx: l_idx,
+        col_ptr: l_ptr,
+    };
+    let u = CscMatrix {
+        rows: n,
+        cols: n,
+        val: u_val,
+        row_idx: u_idx,
+        col_ptr: u_ptr,
+    };
+    Ok((l, u, pinv))
+}
+
+// Nonzero pattern of L \ b in topological order, found by depth-first search
+// over the graph of the columns of L computed so far.
+fn reach(
+    l_ptr: &[usize],
+    l_idx: &[usize],
+    pinv: &[usize],
+    b_idx: &[usize],
+    mark: &mut [bool],
+    stack: &mut Vec<(usize, usize)>,
+    out: &mut Vec<usize>,
+) {
+    let col_end = |jj: usize| {
+        if jj + 1 < l_ptr.len() {
+            l_ptr[jj + 1]
+        } else {
+            l_idx.len()
+        }
+    };
+
+    out.clear();
+    for &start in b_idx.iter() {
+        if mark[start] {
+            continue;
+        }
+        mark[start] = true;
+        let first = match pinv[start] {
+            usize::MAX => 0,
+            jj => l_ptr[jj],
+        };
+        stack.push((start, first));
+        while let Some(&mut (j, ref mut p)) = stack.last_mut() {
+            let end = match pinv[j] {
+                usize::MAX => 0,
+                jj => col_end(jj),
+            };
+            let mut child = None;
+            while *p < end {
+                let i = l_idx[*p];
+                *p += 1;
+                if !mark[i] {
+                    child = Some(i);
+                    break;
+                }
+            }
+            match child {
+                Some(i) => {
+                    mark[i] = true;
+                    let first = match pinv[i] {
+                        usize::MAX => 0,
+                        jj => l_ptr[jj],
+                    };
+                    stack.push((i, first));
+                }
+                None => {
+                    stack.pop();
+                    out.push(j);
+                }
+            }
+        }
+    }
+    for &j in out.iter() {
+        mark[j] = false;
+    }
+    out.reverse();
+}
+
+fn sort_columns<T: Copy>(ptr: &[usize], idx: &mut [usize], val: &mut [T]) {
+    let mut buf = Vec::new();
+    for j in 0..ptr.len() - 1 {
+        let (lo, hi) = (ptr[j], ptr[j + 1]);
+        buf.clear();
+        buf.extend(idx[lo..hi].iter().copied().zip(val[lo..hi].iter().copied()));
+        buf.sort_unstable_by_key(|&(i, _)| i);
+        for (n, (i, v)) in buf.iter().enumerate() {
+            idx[lo + n] = *i;
+            val[lo + n] = *v;
+        }
+    }
+}
+
+impl<T> SparseMatrix<T>
+where
+    T: Float,
+{
+    pub fn lu(&self, q: Option<&[usize]>, tol: T) -> Result<SparseLu<T>, LinalgError> {
+        SparseLu::new(self, q, tol)
+    }
+}
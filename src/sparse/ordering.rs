@@ -0,0 +1,202 @@
+use crate::sparse::SparseMatrix;
+use std::collections::{BTreeSet, VecDeque};
+
+// Orderings are returned as perm with perm[new] = old, so that
+// a.permute(&perm, &perm) is the reordered matrix.
+
+// adjacency of the graph of A + A^T without self loops
+fn adjacency<T>(a: &SparseMatrix<T>) -> Vec<Vec<usize>> {
+    assert_eq!(a.rows, a.cols, "The matrix must be square.");
+    let mut adj = vec![Vec::new(); a.rows];
+    for i in 0..a.rows {
+        for &j in a.col_idx[a.row_ptr[i]..a.row_ptr[i + 1]].iter() {
+            if i != j {
+                adj[i].push(j);
+                adj[j].push(i);
+            }
+        }
+    }
+    for v in adj.iter_mut() {
+        v.sort_unstable();
+        v.dedup();
+    }
+    adj
+}
+
+// BFS levels from root, restricted to nodes not yet numbered
+fn levels(
+    adj: &[Vec<usize>],
+    root: usize,
+    done: &[bool],
+    mark: &mut [usize],
+    stamp: usize,
+) -> Vec<Vec<usize>> {
+    let mut ret = vec![vec![root]];
+    mark[root] = stamp;
+    loop {
+        let mut next = Vec::new();
+        for &u in ret.last().unwrap().iter() {
+            for &v in adj[u].iter() {
+                if !done[v] && mark[v] != stamp {
+                    mark[v] = stamp;
+                    next.push(v);
+                }
+            }
+        }
+        if next.is_empty() {
+            return ret;
+        }
+        ret.push(next);
+    }
+}
+
+impl<T> SparseMatrix<T> {
+    // Reverse Cuthill-McKee ordering for bandwidth reduction. Each connected component
+    // starts from a pseudo-peripheral node found with the George-Liu heuristic.
+    pub fn rcm(&self) -> Vec<usize> {
+        let adj = adjacency(self);
+        let n = adj.len();
+        let mut done = vec![false; n];
+        let mut mark = vec![usize::MAX; n];
+        let mut stamp = 0;
+        let mut order = Vec::with_capacity(n);
+
+        let mut by_degree: Vec<_> = (0..n).collect();
+        by_degree.sort_by_key(|&i| adj[i].len());
+        for &seed in by_degree.iter() {
+            if done[seed] {
+                continue;
+            }
+            let mut root = seed;
+            let mut lv = levels(&adj, root, &done, &mut mark, stamp);
+            stamp += 1;
+            loop {
+                let far = *lv
+                    .last()
+                    .unwrap()
+                    .iter()
+                    .min_by_key(|&&v| adj[v].len())
+                    .unwrap();
+                let next = levels(&adj, far, &done, &mut mark, stamp);
+                stamp += 1;
+                if next.len() <= lv.len() {
+                    break;
+                }
+                root = far;
+                lv = next;
+            }
+
+            let mut queue = VecDeque::from([root]);
+            done[root] = true;
+            while let Some(u) = queue.pop_front() {
+                order.push(u);
+                let mut nbrs: Vec<_> = adj[u].iter().copied().filter(|&v| !done[v]).collect();
+                nbrs.sort_by_key(|&v| adj[v].len());
+                for v in nbrs {
+                    done[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        order.reverse();
+        order
+    }
+
+    // Approximate minimum degree ordering for fill reduction. The elimination is
+    // simulated on the quotient graph, and degrees are replaced by the AMD upper bound
+    // min(n - k, d_i + |L_p \ i|, |A_i \ i| + |L_p \ i| + sum_e |L_e \ L_p|).
+    pub fn amd(&self) -> Vec<usize> {
+        let mut vars = adjacency(self);
+        let n = vars.len();
+        let mut elems: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut elem_vars: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut absorbed = vec![false; n];
+        let mut degree: Vec<_> = vars.iter().map(|v| v.len()).collect();
+        let mut queue: BTreeSet<_> = (0..n).map(|i| (degree[i], i)).collect();
+        let mut in_lp = vec![usize::MAX; n];
+        let mut w = vec![usize::MAX; n];
+        let mut order = Vec::with_capacity(n);
+
+        for k in 0..n {
+            let (_, p) = queue.pop_first().unwrap();
+            order.push(p);
+            in_lp[p] = k;
+
+            // pattern of the new element p
+            let mut lp = Vec::new();
+            for &i in vars[p].iter() {
+                if in_lp[i] != k {
+                    in_lp[i] = k;
+                    lp.push(i);
+                }
+            }
+            for &e in elems[p].iter() {
+                for &i in elem_vars[e].iter() {
+                    if in_lp[i] != k {
+                        in_lp[i] = k;
+                        lp.push(i);
+                    }
+                }
+                absorbed[e] = true;
+                elem_vars[e] = Vec::new();
+            }
+            vars[p] = Vec::new();
+            elems[p] = Vec::new();
+
+            // element lists lose the absorbed elements, variable lists are pruned by L_p
+            for &i in lp.iter() {
+                elems[i].retain(|&e| !absorbed[e]);
+                vars[i].retain(|&j| in_lp[j] != k);
+            }
+
+            // w[e] = |L_e \ L_p|
+            let mut touched = Vec::new();
+            for &i in lp.iter() {
+                for &e in elems[i].iter() {
+                    if w[e] == usize::MAX {
+                        w[e] = elem_vars[e].len();
+                        touched.push(e);
+                    }
+                    w[e] -= 1;
+                }
+            }
+
+            let remaining = n - k - 1;
+            for &i in lp.iter() {
+                let ext: usize = elems[i].iter().map(|&e| w[e]).sum();
+                let d = remaining
+                    .min(degree[i] + lp.len() - 1)
+                    .min(vars[i].len() + lp.len() - 1 + ext);
+                queue.remove(&(degree[i], i));
+                degree[i] = d;
+                queue.insert((d, i));
+                elems[i].push(p);
+            }
+            for e in touched {
+                w[e] = usize::MAX;
+            }
+            elem_vars[p] = lp;
+        }
+        order
+    }
+
+    // max |i - j| over the stored entries
+    pub fn bandwidth(&self) -> usize {
+        (0..self.rows)
+            .flat_map(|i| {
+                self.col_idx[self.row_ptr[i]..self.row_ptr[i + 1]]
+                    .iter()
+                    .map(move |&j| i.abs_diff(j))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    // size of the lower envelope: sum over rows of i - (first column in row i)
+    pub fn profile(&self) -> usize {
+        (0..self.rows)
+            .filter(|&i| self.row_ptr[i] < self.row_ptr[i + 1])
+            .map(|i| i.saturating_sub(self.col_idx[self.row_ptr[i]]))
+            .sum()
+    }
+}
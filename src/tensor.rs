@@ -1,6 +1,412 @@
-#[allow(dead_code)]
-#[derive(Clone, Debug)]
+pub mod einsum;
+mod matmul;
+
+pub use einsum::einsum;
+
+use crate::Matrix;
+use num_traits::Zero;
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+// Dense N-dimensional array stored in row-major (C) order: the last axis is contiguous.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Tensor<T, const D: usize> {
     pub(crate) dims: [usize; D],
     pub(crate) array: Vec<T>,
 }
+
+// Number of entries for a shape, None if it does not fit in usize.
+pub(crate) fn checked_size(dims: &[usize]) -> Option<usize> {
+    dims.iter().try_fold(1_usize, |n, &d| n.checked_mul(d))
+}
+
+pub(crate) fn size(dims: &[usize]) -> usize {
+    checked_size(dims).unwrap_or_else(|| panic!("The tensor size {:?} overflows usize.", dims))
+}
+
+pub(crate) fn strides(dims: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; dims.len()];
+    for k in (0..dims.len().saturating_sub(1)).rev() {
+        strides[k] = strides[k + 1] * dims[k + 1];
+    }
+    strides
+}
+
+// Walks an index over dims in row-major order, where a unit step along axis k moves
+// by steps[k] in array, and copies the entries it visits.
+pub(crate) fn gather<T>(array: &[T], dims: &[usize], steps: &[usize]) -> Vec<T>
+where
+    T: Clone,
+{
+    let len = size(dims);
+    let mut ret = Vec::with_capacity(len);
+    let mut idx = vec![0; dims.len()];
+    let mut offset = 0;
+    for _ in 0..len {
+        ret.push(array[offset].clone());
+        for k in (0..dims.len()).rev() {
+            idx[k] += 1;
+            offset += steps[k];
+            if idx[k] < dims[k] {
+                break;
+            }
+            offset -= steps[k] * dims[k];
+            idx[k] = 0;
+        }
+    }
+    ret
+}
+
+impl<T, const D: usize> Tensor<T, D> {
+    pub fn from_vec(array: Vec<T>, dims: [usize; D]) -> Self {
+        assert_eq!(
+            array.len(),
+            size(&dims),
+            "The array length must be equal the tensor size {:?}.",
+            dims
+        );
+        Self { dims, array }
+    }
+
+    pub fn shape(&self) -> [usize; D] {
+        self.dims
+    }
+
+    pub fn ndim(&self) -> usize {
+        D
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    pub fn to_slice(&self) -> &[T] {
+        &self.array
+    }
+
+    fn offset(&self, idx: &[usize; D]) -> Option<usize> {
+        let mut offset = 0;
+        for (&i, &n) in idx.iter().zip(self.dims.iter()) {
+            if i >= n {
+                return None;
+            }
+            offset = offset * n + i;
+        }
+        Some(offset)
+    }
+
+    pub fn get_ref(&self, idx: [usize; D]) -> Option<&T> {
+        self.offset(&idx).map(|n| &self.array[n])
+    }
+
+    pub fn get_mut(&mut self, idx: [usize; D]) -> Option<&mut T> {
+        self.offset(&idx).map(|n| &mut self.array[n])
+    }
+
+    // Same data under a new shape with the same number of entries; no copy is made.
+    pub fn reshape<const E: usize>(self, dims: [usize; E]) -> Tensor<T, E> {
+        assert_eq!(
+            self.array.len(),
+            size(&dims),
+            "{:?} cannot reshape to {:?}",
+            self.dims,
+            dims
+        );
+        Tensor {
+            dims,
+            array: self.array,
+        }
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Tensor<U, D>
+    where
+        F: Fn(&T) -> U,
+    {
+        Tensor {
+            dims: self.dims,
+            array: self.array.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Zero + Clone,
+{
+    pub fn zeros(dims: [usize; D]) -> Self {
+        Self {
+            dims,
+            array: vec![T::zero(); size(&dims)],
+        }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Clone,
+{
+    pub fn get(&self, idx: [usize; D]) -> Option<T> {
+        self.get_ref(idx).cloned()
+    }
+
+    // Axis k of the result is axis axes[k] of self.
+    pub fn permute(&self, axes: [usize; D]) -> Self {
+        let mut seen = [false; D];
+        for &a in axes.iter() {
+            assert!(
+                a < D && !seen[a],
+                "{:?} is not a permutation of the axes.",
+                axes
+            );
+            seen[a] = true;
+        }
+        let src = strides(&self.dims);
+        let dims = axes.map(|a| self.dims[a]);
+        let steps = axes.map(|a| src[a]);
+        Self {
+            dims,
+            array: gather(&self.array, &dims, &steps),
+        }
+    }
+
+    pub fn transpose_axes(&self, a: usize, b: usize) -> Self {
+        for axis in [a, b] {
+            assert!(
+                axis < D,
+                "Axis {} is out of range for {} dimensions.",
+                axis,
+                D
+            );
+        }
+        let mut axes = std::array::from_fn(|k| k);
+        axes.swap(a, b);
+        self.permute(axes)
+    }
+
+    // Folds along one axis, keeping it with length 1; None folds everything. An empty
+    // axis folds to empty, the identity of the reduction, which max and min do not have.
+    fn fold_axis<F>(&self, axis: Option<usize>, empty: Option<T>, f: F) -> Self
+    where
+        F: Fn(T, &T) -> T,
+    {
+        let Some(axis) = axis else {
+            let mut it = self.array.iter();
+            let value = match it.next() {
+                Some(first) => it.fold(first.clone(), f),
+                None => empty.expect("Cannot reduce an empty tensor."),
+            };
+            return Self {
+                dims: [1; D],
+                array: vec![value],
+            };
+        };
+        assert!(
+            axis < D,
+            "Axis {} is out of range for {} dimensions.",
+            axis,
+            D
+        );
+        let inner: usize = self.dims[axis + 1..].iter().product();
+        let len = self.dims[axis];
+        let mut dims = self.dims;
+        dims[axis] = 1;
+        if len == 0 {
+            let value = empty.expect("Cannot reduce an empty axis.");
+            return Self {
+                array: vec![value; size(&dims)],
+                dims,
+            };
+        }
+        // an empty axis after this one leaves nothing to fold
+        if inner == 0 {
+            return Self {
+                dims,
+                array: Vec::new(),
+            };
+        }
+        let mut array = Vec::with_capacity(self.array.len() / len);
+        for block in self.array.chunks(len * inner) {
+            let (head, rest) = block.split_at(inner);
+            let mut acc = head.to_vec();
+            for slab in rest.chunks(inner) {
+                for (a, x) in acc.iter_mut().zip(slab.iter()) {
+                    *a = f(a.clone(), x);
+                }
+            }
+            array.extend(acc);
+        }
+        Self { dims, array }
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: Zero + Clone,
+{
+    pub fn sum(&self, axis: Option<usize>) -> Self {
+        self.fold_axis(axis, Some(T::zero()), |acc, x| acc + x.clone())
+    }
+}
+
+impl<T, const D: usize> Tensor<T, D>
+where
+    T: PartialOrd + Clone,
+{
+    pub fn max(&self, axis: Option<usize>) -> Self {
+        self.fold_axis(axis, None, |acc, x| if *x > acc { x.clone() } else { acc })
+    }
+
+    pub fn min(&self, axis: Option<usize>) -> Self {
+        self.fold_axis(axis, None, |acc, x| if *x < acc { x.clone() } else { acc })
+    }
+}
+
+impl<T, const D: usize> Index<[usize; D]> for Tensor<T, D> {
+    type Output = T;
+
+    fn index(&self, idx: [usize; D]) -> &Self::Output {
+        match self.offset(&idx) {
+            Some(n) => &self.array[n],
+            None => panic!("Index {:?} is out of bounds for {:?}.", idx, self.dims),
+        }
+    }
+}
+
+impl<T, const D: usize> IndexMut<[usize; D]> for Tensor<T, D> {
+    fn index_mut(&mut self, idx: [usize; D]) -> &mut Self::Output {
+        match self.offset(&idx) {
+            Some(n) => &mut self.array[n],
+            None => panic!("Index {:?} is out of bounds for {:?}.", idx, self.dims),
+        }
+    }
+}
+
+impl<T> From<Matrix<T>> for Tensor<T, 2> {
+    fn from(mat: Matrix<T>) -> Self {
+        Self {
+            dims: [mat.rows, mat.cols],
+            array: mat.array,
+        }
+    }
+}
+
+impl<T> From<Tensor<T, 2>> for Matrix<T> {
+    fn from(tensor: Tensor<T, 2>) -> Self {
+        let [rows, cols] = tensor.dims;
+        Matrix::from_vec(tensor.array, rows, cols)
+    }
+}
+
+// Elementwise operators between tensors of the same shape, and with a scalar on the right.
+macro_rules! deftensorop {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<T, const D: usize> $trait<&Tensor<T, D>> for &Tensor<T, D>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Tensor<T, D>;
+
+            fn $method(self, rhs: &Tensor<T, D>) -> Self::Output {
+                assert_eq!(
+                    self.dims, rhs.dims,
+                    "Shapes {:?} and {:?} differ.",
+                    self.dims, rhs.dims
+                );
+                Tensor {
+                    dims: self.dims,
+                    array: self
+                        .array
+                        .iter()
+                        .zip(rhs.array.iter())
+                        .map(|(&x, &y)| x $op y)
+                        .collect(),
+                }
+            }
+        }
+
+        impl<T, const D: usize> $trait<&Tensor<T, D>> for Tensor<T, D>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Tensor<T, D>;
+
+            fn $method(self, rhs: &Tensor<T, D>) -> Self::Output {
+                &self $op rhs
+            }
+        }
+
+        impl<T, const D: usize> $trait<Tensor<T, D>> for &Tensor<T, D>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Tensor<T, D>;
+
+            fn $method(self, rhs: Tensor<T, D>) -> Self::Output {
+                self $op &rhs
+            }
+        }
+
+        impl<T, const D: usize> $trait<Tensor<T, D>> for Tensor<T, D>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Tensor<T, D>;
+
+            fn $method(self, rhs: Tensor<T, D>) -> Self::Output {
+                &self $op &rhs
+            }
+        }
+
+        impl<T, const D: usize> $trait<T> for &Tensor<T, D>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Tensor<T, D>;
+
+            fn $method(self, rhs: T) -> Self::Output {
+                self.map(|&x| x $op rhs)
+            }
+        }
+
+        impl<T, const D: usize> $trait<T> for Tensor<T, D>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Tensor<T, D>;
+
+            fn $method(self, rhs: T) -> Self::Output {
+                &self $op rhs
+            }
+        }
+    };
+}
+
+deftensorop!(Add, add, +);
+deftensorop!(Sub, sub, -);
+deftensorop!(Mul, mul, *);
+deftensorop!(Div, div, /);
+
+impl<T, const D: usize> Neg for &Tensor<T, D>
+where
+    T: Neg<Output = T> + Copy,
+{
+    type Output = Tensor<T, D>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|&x| -x)
+    }
+}
+
+impl<T, const D: usize> Neg for Tensor<T, D>
+where
+    T: Neg<Output = T> + Copy,
+{
+    type Output = Tensor<T, D>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
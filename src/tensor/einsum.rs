@@ -0,0 +1,418 @@
+use crate::{
+    ops::gemm::{gemm_acc, Element, Operand},
+    tensor::{gather, size, strides, Tensor},
+    Matrix,
+};
+use num_traits::{One, Zero};
+use std::collections::HashMap;
+
+// Anything that can be fed to einsum: a row-major array with a runtime shape.
+pub trait EinsumOperand<T> {
+    fn dims(&self) -> Vec<usize>;
+    fn data(&self) -> &[T];
+}
+
+impl<T, const D: usize> EinsumOperand<T> for Tensor<T, D> {
+    fn dims(&self) -> Vec<usize> {
+        self.dims.to_vec()
+    }
+
+    fn data(&self) -> &[T] {
+        &self.array
+    }
+}
+
+impl<T> EinsumOperand<T> for Matrix<T> {
+    fn dims(&self) -> Vec<usize> {
+        vec![self.rows, self.cols]
+    }
+
+    fn data(&self) -> &[T] {
+        &self.array
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EinsumError {
+    InvalidSubscripts(String),
+    OperandCount {
+        expected: usize,
+        found: usize,
+    },
+    RankMismatch {
+        operand: usize,
+        subscripts: usize,
+        rank: usize,
+    },
+    DimensionMismatch {
+        index: char,
+        first: usize,
+        second: usize,
+    },
+    UnknownOutputIndex(char),
+    OutputRank {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl std::fmt::Display for EinsumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EinsumError::InvalidSubscripts(spec) => {
+                write!(f, "Invalid einsum subscripts {:?}.", spec)
+            }
+            EinsumError::OperandCount { expected, found } => write!(
+                f,
+                "The subscripts name {} operands, got {}.",
+                expected, found
+            ),
+            EinsumError::RankMismatch {
+                operand,
+                subscripts,
+                rank,
+            } => write!(
+                f,
+                "Operand {} has {} dimensions but {} subscripts.",
+                operand, rank, subscripts
+            ),
+            EinsumError::DimensionMismatch {
+                index,
+                first,
+                second,
+            } => write!(
+                f,
+                "Index {:?} is used with lengths {} and {}.",
+                index, first, second
+            ),
+            EinsumError::UnknownOutputIndex(index) => {
+                write!(f, "Output index {:?} does not appear in any input.", index)
+            }
+            EinsumError::OutputRank { expected, found } => write!(
+                f,
+                "The output has {} subscripts but the result tensor {} dimensions.",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EinsumError {}
+
+// Intermediate operand of runtime rank; idx[k] labels axis k.
+struct Term<T> {
+    idx: Vec<char>,
+    dims: Vec<usize>,
+    array: Vec<T>,
+}
+
+impl<T> Term<T>
+where
+    T: Zero + Copy,
+{
+    // Merges repeated labels into one axis by walking the diagonal, e.g. "ii" -> "i".
+    fn diagonal(self) -> Self {
+        let mut idx: Vec<char> = Vec::new();
+        for &c in self.idx.iter() {
+            if !idx.contains(&c) {
+                idx.push(c);
+            }
+        }
+        if idx.len() == self.idx.len() {
+            return self;
+        }
+        let src = strides(&self.dims);
+        let mut dims = vec![0; idx.len()];
+        let mut steps = vec![0; idx.len()];
+        for (k, c) in self.idx.iter().enumerate() {
+            let q = idx.iter().position(|d| d == c).unwrap();
+            dims[q] = self.dims[k];
+            steps[q] += src[k];
+        }
+        Self {
+            array: gather(&self.array, &dims, &steps),
+            idx,
+            dims,
+        }
+    }
+
+    // Reorders the axes to the given labels, which must be a permutation of idx.
+    fn permute(&self, idx: &[char]) -> Self {
+        let src = strides(&self.dims);
+        let axes: Vec<usize> = idx
+            .iter()
+            .map(|c| self.idx.iter().position(|d| d == c).unwrap())
+            .collect();
+        let dims: Vec<usize> = axes.iter().map(|&a| self.dims[a]).collect();
+        let steps: Vec<usize> = axes.iter().map(|&a| src[a]).collect();
+        Self {
+            idx: idx.to_vec(),
+            array: gather(&self.array, &dims, &steps),
+            dims,
+        }
+    }
+
+    // Sums out every axis whose label fails keep.
+    fn reduce<F>(self, keep: F) -> Self
+    where
+        F: Fn(char) -> bool,
+    {
+        let (kept, summed): (Vec<char>, Vec<char>) = self.idx.iter().partition(|&&c| keep(c));
+        if summed.is_empty() {
+            return self;
+        }
+        let order: Vec<char> = kept.iter().chain(summed.iter()).copied().collect();
+        let moved = self.permute(&order);
+        let len: usize = moved.dims[kept.len()..].iter().product();
+        let dims = moved.dims[..kept.len()].to_vec();
+        let array = if len == 0 {
+            vec![T::zero(); size(&dims)]
+        } else {
+            moved
+                .array
+                .chunks(len)
+                .map(|c| c.iter().fold(T::zero(), |acc, &x| acc + x))
+                .collect()
+        };
+        Self {
+            idx: kept,
+            dims,
+            array,
+        }
+    }
+
+    fn len_of(&self, c: char) -> usize {
+        self.dims[self.idx.iter().position(|&d| d == c).unwrap()]
+    }
+}
+
+// Contracts two terms as a batch of matrix products: labels shared and still needed
+// are batch axes, shared and not needed are summed, and the rest are the free rows
+// of x and columns of y.
+fn contract<T>(x: &Term<T>, y: &Term<T>, needed: &[char]) -> Term<T>
+where
+    T: Element + One,
+{
+    let shared = |c: &&char| y.idx.contains(c);
+    let batch: Vec<char> = x
+        .idx
+        .iter()
+        .filter(|c| shared(c) && needed.contains(c))
+        .copied()
+        .collect();
+    let inner: Vec<char> = x
+        .idx
+        .iter()
+        .filter(|c| shared(c) && !needed.contains(c))
+        .copied()
+        .collect();
+    let xfree: Vec<char> = x.idx.iter().filter(|c| !shared(c)).copied().collect();
+    let yfree: Vec<char> = y
+        .idx
+        .iter()
+        .filter(|c| !x.idx.contains(c))
+        .copied()
+        .collect();
+
+    let extent = |labels: &[char]| labels.iter().map(|&c| x.len_of(c)).product::<usize>();
+    let (b, m, k) = (extent(&batch), extent(&xfree), extent(&inner));
+    let n: usize = yfree.iter().map(|&c| y.len_of(c)).product();
+
+    let xp = x.permute(&[&batch[..], &xfree[..], &inner[..]].concat());
+    let yp = y.permute(&[&batch[..], &inner[..], &yfree[..]].concat());
+    let mut array = vec![T::zero(); size(&[b, m, n])];
+    if m * n * k != 0 {
+        let slab = |data, rows, cols| Operand {
+            data,
+            rows,
+            cols,
+            rs: cols,
+            cs: 1,
+        };
+        for ((c, lhs), rhs) in array
+            .chunks_mut(m * n)
+            .zip(xp.array.chunks(m * k))
+            .zip(yp.array.chunks(k * n))
+        {
+            gemm_acc(T::one(), slab(lhs, m, k), slab(rhs, k, n), c, n);
+        }
+    }
+
+    let idx = [&batch[..], &xfree[..], &yfree[..]].concat();
+    let dims = idx
+        .iter()
+        .map(|&c| {
+            if x.idx.contains(&c) {
+                x.len_of(c)
+            } else {
+                y.len_of(c)
+            }
+        })
+        .collect();
+    Term { idx, dims, array }
+}
+
+// Labels of term i that must survive: those in the output or in another term.
+fn needed<T>(terms: &[Term<T>], skip: &[usize], output: &[char]) -> Vec<char> {
+    let mut labels = output.to_vec();
+    for (t, term) in terms.iter().enumerate() {
+        if !skip.contains(&t) {
+            labels.extend(term.idx.iter().copied());
+        }
+    }
+    labels
+}
+
+fn parse(spec: &str, ranks: &[usize]) -> Result<(Vec<Vec<char>>, Vec<char>), EinsumError> {
+    let spec: String = spec.chars().filter(|c| !c.is_whitespace()).collect();
+    let invalid = || EinsumError::InvalidSubscripts(spec.clone());
+    let (lhs, rhs) = match spec.split_once("->") {
+        Some((lhs, rhs)) => (lhs, Some(rhs)),
+        None => (&spec[..], None),
+    };
+    let inputs: Vec<Vec<char>> = lhs.split(',').map(|s| s.chars().collect()).collect();
+    if inputs.iter().flatten().any(|c| !c.is_ascii_alphabetic()) {
+        return Err(invalid());
+    }
+    if inputs.len() != ranks.len() {
+        return Err(EinsumError::OperandCount {
+            expected: inputs.len(),
+            found: ranks.len(),
+        });
+    }
+    for (operand, (labels, &rank)) in inputs.iter().zip(ranks.iter()).enumerate() {
+        if labels.len() != rank {
+            return Err(EinsumError::RankMismatch {
+                operand,
+                subscripts: labels.len(),
+                rank,
+            });
+        }
+    }
+
+    let output = match rhs {
+        Some(rhs) => {
+            let output: Vec<char> = rhs.chars().collect();
+            for (k, c) in output.iter().enumerate() {
+                if !c.is_ascii_alphabetic() || output[..k].contains(c) {
+                    return Err(invalid());
+                }
+                if !inputs.iter().flatten().any(|d| d == c) {
+                    return Err(EinsumError::UnknownOutputIndex(*c));
+                }
+            }
+            output
+        }
+        // Implicit mode keeps the labels used exactly once, in alphabetical order.
+        None => {
+            let mut output: Vec<char> = inputs
+                .iter()
+                .flatten()
+                .filter(|&c| inputs.iter().flatten().filter(|&d| d == c).count() == 1)
+                .copied()
+                .collect();
+            output.sort_unstable();
+            output
+        }
+    };
+    Ok((inputs, output))
+}
+
+// Einstein summation, e.g. einsum("ij,jk->ik", &[&a, &b]) for a matrix product,
+// "bij,bjk->bik" for a batched one, "ii->" for a trace or "i,j->ij" for an outer product.
+// Operands are contracted pairwise, cheapest pair first, and every pairwise contraction
+// runs as a batch of GEMM calls on the permuted operands.
+pub fn einsum<T, const E: usize>(
+    spec: &str,
+    operands: &[&dyn EinsumOperand<T>],
+) -> Result<Tensor<T, E>, EinsumError>
+where
+    T: Element + One,
+{
+    let shapes: Vec<Vec<usize>> = operands.iter().map(|op| op.dims()).collect();
+    let ranks: Vec<usize> = shapes.iter().map(|s| s.len()).collect();
+    let (inputs, output) = parse(spec, &ranks)?;
+    if output.len() != E {
+        return Err(EinsumError::OutputRank {
+            expected: E,
+            found: output.len(),
+        });
+    }
+
+    let mut lens: HashMap<char, usize> = HashMap::new();
+    for (labels, shape) in inputs.iter().zip(shapes.iter()) {
+        for (&c, &n) in labels.iter().zip(shape.iter()) {
+            let first = *lens.entry(c).or_insert(n);
+            if first != n {
+                return Err(EinsumError::DimensionMismatch {
+                    index: c,
+                    first,
+                    second: n,
+                });
+            }
+        }
+    }
+
+    let mut terms: Vec<Term<T>> = inputs
+        .into_iter()
+        .zip(shapes)
+        .zip(operands.iter())
+        .map(|((idx, dims), op)| {
+            Term {
+                idx,
+                dims,
+                array: op.data().to_vec(),
+            }
+            .diagonal()
+        })
+        .collect();
+
+    // Labels private to a single term are summed before it takes part in a product.
+    for t in 0..terms.len() {
+        let keep = needed(&terms, &[t], &output);
+        let term = std::mem::replace(
+            &mut terms[t],
+            Term {
+                idx: vec![],
+                dims: vec![],
+                array: vec![],
+            },
+        );
+        terms[t] = term.reduce(|c| keep.contains(&c));
+    }
+
+    while terms.len() > 1 {
+        // Greedy order: the pair with the fewest multiply-adds, then the smallest result.
+        let mut best = (usize::MAX, usize::MAX, 0, 1);
+        for i in 0..terms.len() {
+            for j in i + 1..terms.len() {
+                let keep = needed(&terms, &[i, j], &output);
+                let mut labels = terms[i].idx.clone();
+                labels.extend(terms[j].idx.iter().filter(|c| !terms[i].idx.contains(c)));
+                let flops = labels.iter().map(|c| lens[c]).product::<usize>();
+                let size = labels
+                    .iter()
+                    .filter(|c| keep.contains(c))
+                    .map(|c| lens[c])
+                    .product::<usize>();
+                if (flops, size) < (best.0, best.1) {
+                    best = (flops, size, i, j);
+                }
+            }
+        }
+        let (i, j) = (best.2, best.3);
+        let keep = needed(&terms, &[i, j], &output);
+        let y = terms.remove(j);
+        let x = terms.remove(i);
+        terms.push(contract(&x, &y, &keep));
+    }
+
+    let term = terms.pop().unwrap().reduce(|c| output.contains(&c));
+    let term = term.permute(&output);
+    let mut dims = [0; E];
+    dims.copy_from_slice(&term.dims);
+    Ok(Tensor {
+        dims,
+        array: term.array,
+    })
+}
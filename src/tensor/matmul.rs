@@ -0,0 +1,122 @@
+use crate::{
+    error::MatrixError,
+    ops::gemm::{gemm_acc, Element, Operand},
+    tensor::{size, Tensor},
+    Matrix,
+};
+use num_traits::One;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// (batch, rows, cols) stack of row-major matrices; batch entry b starts at b * rows * cols.
+fn slab<T>(t: &Tensor<T, 3>, b: usize) -> Operand<'_, T> {
+    let [batch, rows, cols] = t.dims;
+    // a batch of one is reused for every output matrix
+    let b = if batch == 1 { 0 } else { b };
+    Operand {
+        data: &t.array[b * rows * cols..(b + 1) * rows * cols],
+        rows,
+        cols,
+        rs: cols,
+        cs: 1,
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn batch_loop<T>(lhs: &Tensor<T, 3>, rhs: &Tensor<T, 3>, c: &mut [T], len: usize)
+where
+    T: Element + One,
+{
+    for (b, cc) in c.chunks_mut(len).enumerate() {
+        gemm_acc(T::one(), slab(lhs, b), slab(rhs, b), cc, rhs.dims[2]);
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn batch_loop<T>(lhs: &Tensor<T, 3>, rhs: &Tensor<T, 3>, c: &mut [T], len: usize)
+where
+    T: Element + One,
+{
+    c.par_chunks_mut(len).enumerate().for_each(|(b, cc)| {
+        gemm_acc(T::one(), slab(lhs, b), slab(rhs, b), cc, rhs.dims[2]);
+    });
+}
+
+impl<T> Tensor<T, 3>
+where
+    T: Element + One,
+{
+    // (b, m, k) x (b, k, n) -> (b, m, n), one matrix product per batch entry. A batch
+    // dimension of 1 broadcasts against the other operand.
+    pub fn batched_matmul(&self, rhs: &Tensor<T, 3>) -> Tensor<T, 3> {
+        let [b1, m, k] = self.dims;
+        let [b2, k2, n] = rhs.dims;
+        assert_eq!(
+            k, k2,
+            "Shapes {:?} and {:?} are incompatible for batched_matmul.",
+            self.dims, rhs.dims
+        );
+        let batch = match (b1, b2) {
+            (b1, b2) if b1 == b2 => b1,
+            (1, b2) => b2,
+            (b1, 1) => b1,
+            _ => panic!(
+                "Batch dimensions {} and {} cannot broadcast for batched_matmul.",
+                b1, b2
+            ),
+        };
+
+        let mut array = vec![T::zero(); size(&[batch, m, n])];
+        if m * n != 0 {
+            batch_loop(self, rhs, &mut array, m * n);
+        }
+        Tensor {
+            dims: [batch, m, n],
+            array,
+        }
+    }
+}
+
+// Splits along the leading axis; every matrix needs a row and a column, even when
+// there are no matrices to build.
+impl<T> TryFrom<Tensor<T, 3>> for Vec<Matrix<T>> {
+    type Error = MatrixError;
+
+    fn try_from(tensor: Tensor<T, 3>) -> Result<Self, MatrixError> {
+        let [batch, rows, cols] = tensor.dims;
+        if rows == 0 || cols == 0 {
+            return Err(MatrixError::ZeroDimension { rows, cols });
+        }
+        let mut array = tensor.array.into_iter();
+        Ok((0..batch)
+            .map(|_| Matrix {
+                rows,
+                cols,
+                array: array.by_ref().take(rows * cols).collect(),
+            })
+            .collect())
+    }
+}
+
+// Stacks matrices of one shape along a new leading axis.
+impl<T> TryFrom<Vec<Matrix<T>>> for Tensor<T, 3> {
+    type Error = MatrixError;
+
+    fn try_from(mats: Vec<Matrix<T>>) -> Result<Self, MatrixError> {
+        let shape = mats.first().ok_or(MatrixError::EmptyStack)?.shape();
+        if let Some(mat) = mats.iter().find(|mat| mat.shape() != shape) {
+            return Err(MatrixError::ShapeMismatch {
+                op: "stack",
+                lhs: shape,
+                rhs: mat.shape(),
+            });
+        }
+        let dims = [mats.len(), shape.0, shape.1];
+        let mut array = Vec::with_capacity(size(&dims));
+        for mat in mats {
+            array.extend(mat.array);
+        }
+        Ok(Tensor { dims, array })
+    }
+}
@@ -0,0 +1,513 @@
+use crate::{
+    ops::gemm::{gemm_acc, Element, Operand},
+    Axis, Matrix,
+};
+use num_traits::One;
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Bound, Mul, Range, RangeBounds, Sub, SubAssign},
+};
+
+// Borrowed rows x cols window into a row-major buffer. Entry (i, j) lives at
+// data[i * rs + j * cs], so slices, rows, columns and transposes are all just
+// different strides over the same storage.
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T> {
+    data: &'a [T],
+    rows: usize,
+    cols: usize,
+    rs: usize,
+    cs: usize,
+}
+
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T> {
+    data: &'a mut [T],
+    rows: usize,
+    cols: usize,
+    rs: usize,
+    cs: usize,
+}
+
+fn resolve<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e + 1,
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end && end <= len,
+        "Range {}..{} is out of bounds for length {}.",
+        start,
+        end,
+        len
+    );
+    // like a Matrix, a view always has at least one row and one column
+    assert!(start < end, "Range {}..{} is empty.", start, end);
+    start..end
+}
+
+// offset of the first entry and the shape of a sub-window
+fn window<R, C>(rows: usize, cols: usize, rs: usize, cs: usize, r: R, c: C) -> (usize, usize, usize)
+where
+    R: RangeBounds<usize>,
+    C: RangeBounds<usize>,
+{
+    let (r, c) = (resolve(r, rows), resolve(c, cols));
+    (r.start * rs + c.start * cs, r.len(), c.len())
+}
+
+impl<T> Matrix<T> {
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: &self.array,
+            rows: self.rows,
+            cols: self.cols,
+            rs: self.cols,
+            cs: 1,
+        }
+    }
+
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {
+            rows: self.rows,
+            cols: self.cols,
+            rs: self.cols,
+            cs: 1,
+            data: &mut self.array,
+        }
+    }
+
+    pub fn slice<R, C>(&self, rows: R, cols: C) -> MatrixView<'_, T>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        self.view().slice(rows, cols)
+    }
+
+    pub fn slice_mut<R, C>(&mut self, rows: R, cols: C) -> MatrixViewMut<'_, T>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        self.view_mut().into_slice(rows, cols)
+    }
+
+    pub fn row(&self, i: usize) -> MatrixView<'_, T> {
+        self.slice(i..=i, ..)
+    }
+
+    pub fn col(&self, j: usize) -> MatrixView<'_, T> {
+        self.slice(.., j..=j)
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+        self.slice_mut(i..=i, ..)
+    }
+
+    pub fn col_mut(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+        self.slice_mut(.., j..=j)
+    }
+
+    pub fn t(&self) -> MatrixView<'_, T> {
+        self.view().t()
+    }
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn get_ref(&self, row: usize, col: usize) -> Option<&'a T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.data[row * self.rs + col * self.cs])
+        } else {
+            None
+        }
+    }
+
+    pub fn slice<R, C>(&self, rows: R, cols: C) -> MatrixView<'a, T>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let (offset, rows, cols) = window(self.rows, self.cols, self.rs, self.cs, rows, cols);
+        MatrixView {
+            data: &self.data[offset..],
+            rows,
+            cols,
+            rs: self.rs,
+            cs: self.cs,
+        }
+    }
+
+    pub fn row(&self, i: usize) -> MatrixView<'a, T> {
+        self.slice(i..=i, ..)
+    }
+
+    pub fn col(&self, j: usize) -> MatrixView<'a, T> {
+        self.slice(.., j..=j)
+    }
+
+    pub fn t(&self) -> MatrixView<'a, T> {
+        MatrixView {
+            data: self.data,
+            rows: self.cols,
+            cols: self.rows,
+            rs: self.cs,
+            cs: self.rs,
+        }
+    }
+
+    // entries in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        let data = self.data;
+        (0..self.rows)
+            .flat_map(move |i| (0..self.cols).map(move |j| &data[i * self.rs + j * self.cs]))
+    }
+
+    fn operand(&self) -> Operand<'a, T> {
+        Operand {
+            data: self.data,
+            rows: self.rows,
+            cols: self.cols,
+            rs: self.rs,
+            cs: self.cs,
+        }
+    }
+}
+
+impl<T> MatrixView<'_, T>
+where
+    T: Copy,
+{
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        self.get_ref(row, col).copied()
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            array: self.iter().copied().collect(),
+        }
+    }
+
+    fn zip_with<F>(&self, rhs: &MatrixView<T>, f: F) -> Matrix<T>
+    where
+        F: Fn(T, T) -> T,
+    {
+        assert_eq!(self.shape(), rhs.shape());
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            array: self
+                .iter()
+                .zip(rhs.iter())
+                .map(|(&x, &y)| f(x, y))
+                .collect(),
+        }
+    }
+
+    fn reduce_rows<F>(&self, f: F) -> Vec<T>
+    where
+        F: Fn(&mut dyn Iterator<Item = T>) -> T,
+    {
+        (0..self.rows)
+            .map(|i| f(&mut self.row(i).iter().copied()))
+            .collect()
+    }
+}
+
+impl<T> From<MatrixView<'_, T>> for Matrix<T>
+where
+    T: Copy,
+{
+    fn from(view: MatrixView<'_, T>) -> Self {
+        view.to_matrix()
+    }
+}
+
+impl<T> MatrixView<'_, T>
+where
+    T: Sum + Copy,
+{
+    pub fn sum(&self, ax: Option<Axis>) -> Matrix<T> {
+        match ax {
+            Some(Axis::ROW) => Matrix::from_vec_col(self.reduce_rows(|it| it.sum())),
+            Some(Axis::COLUMN) => Matrix::from_vec_row(self.t().reduce_rows(|it| it.sum())),
+            None => Matrix::new([[self.iter().copied().sum()]]),
+        }
+    }
+}
+
+impl<T> MatrixView<'_, T>
+where
+    T: PartialOrd + Copy,
+{
+    pub fn max(&self, ax: Option<Axis>) -> Matrix<T> {
+        let max =
+            |it: &mut dyn Iterator<Item = T>| it.reduce(|x, y| if x > y { x } else { y }).unwrap();
+        match ax {
+            Some(Axis::ROW) => Matrix::from_vec_col(self.reduce_rows(max)),
+            Some(Axis::COLUMN) => Matrix::from_vec_row(self.t().reduce_rows(max)),
+            None => Matrix::new([[max(&mut self.iter().copied())]]),
+        }
+    }
+
+    pub fn min(&self, ax: Option<Axis>) -> Matrix<T> {
+        let min =
+            |it: &mut dyn Iterator<Item = T>| it.reduce(|x, y| if x < y { x } else { y }).unwrap();
+        match ax {
+            Some(Axis::ROW) => Matrix::from_vec_col(self.reduce_rows(min)),
+            Some(Axis::COLUMN) => Matrix::from_vec_row(self.t().reduce_rows(min)),
+            None => Matrix::new([[min(&mut self.iter().copied())]]),
+        }
+    }
+}
+
+impl<'a, T> MatrixViewMut<'a, T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            data: self.data,
+            rows: self.rows,
+            cols: self.cols,
+            rs: self.rs,
+            cs: self.cs,
+        }
+    }
+
+    pub fn get_ref(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.data[row * self.rs + col * self.cs])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.rows && col < self.cols {
+            Some(&mut self.data[row * self.rs + col * self.cs])
+        } else {
+            None
+        }
+    }
+
+    pub fn slice<R, C>(&mut self, rows: R, cols: C) -> MatrixViewMut<'_, T>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let (offset, rows, cols) = window(self.rows, self.cols, self.rs, self.cs, rows, cols);
+        MatrixViewMut {
+            data: &mut self.data[offset..],
+            rows,
+            cols,
+            rs: self.rs,
+            cs: self.cs,
+        }
+    }
+
+    pub fn into_slice<R, C>(self, rows: R, cols: C) -> MatrixViewMut<'a, T>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let (offset, rows, cols) = window(self.rows, self.cols, self.rs, self.cs, rows, cols);
+        MatrixViewMut {
+            data: &mut self.data[offset..],
+            rows,
+            cols,
+            rs: self.rs,
+            cs: self.cs,
+        }
+    }
+
+    pub fn row(&mut self, i: usize) -> MatrixViewMut<'_, T> {
+        self.slice(i..=i, ..)
+    }
+
+    pub fn col(&mut self, j: usize) -> MatrixViewMut<'_, T> {
+        self.slice(.., j..=j)
+    }
+
+    pub fn t(self) -> MatrixViewMut<'a, T> {
+        MatrixViewMut {
+            data: self.data,
+            rows: self.cols,
+            cols: self.rows,
+            rs: self.cs,
+            cs: self.rs,
+        }
+    }
+
+    fn for_each_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, usize, &mut T),
+    {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                f(i, j, &mut self.data[i * self.rs + j * self.cs]);
+            }
+        }
+    }
+}
+
+impl<T> MatrixViewMut<'_, T>
+where
+    T: Copy,
+{
+    pub fn fill(&mut self, value: T) {
+        self.for_each_mut(|_, _, x| *x = value);
+    }
+
+    pub fn assign(&mut self, src: MatrixView<T>) {
+        assert_eq!(self.shape(), src.shape());
+        self.for_each_mut(|i, j, x| *x = src.data[i * src.rs + j * src.cs]);
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        self.view().to_matrix()
+    }
+}
+
+impl<T> AddAssign<MatrixView<'_, T>> for MatrixViewMut<'_, T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn add_assign(&mut self, rhs: MatrixView<T>) {
+        assert_eq!(self.shape(), rhs.shape());
+        self.for_each_mut(|i, j, x| *x = *x + rhs.data[i * rhs.rs + j * rhs.cs]);
+    }
+}
+
+impl<T> AddAssign<&Matrix<T>> for MatrixViewMut<'_, T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn add_assign(&mut self, rhs: &Matrix<T>) {
+        *self += rhs.view();
+    }
+}
+
+impl<T> SubAssign<MatrixView<'_, T>> for MatrixViewMut<'_, T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    fn sub_assign(&mut self, rhs: MatrixView<T>) {
+        assert_eq!(self.shape(), rhs.shape());
+        self.for_each_mut(|i, j, x| *x = *x - rhs.data[i * rhs.rs + j * rhs.cs]);
+    }
+}
+
+impl<T> SubAssign<&Matrix<T>> for MatrixViewMut<'_, T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    fn sub_assign(&mut self, rhs: &Matrix<T>) {
+        *self -= rhs.view();
+    }
+}
+
+// Binary operators between views and matrices; every combination returns an owned Matrix.
+macro_rules! defviewop {
+    ($trait:ident, $method:ident, ($($bound:tt)*), |$x:ident, $y:ident| $body:expr) => {
+        impl<T> $trait<MatrixView<'_, T>> for MatrixView<'_, T>
+        where
+            T: $($bound)*,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: MatrixView<T>) -> Self::Output {
+                let ($x, $y) = (self, rhs);
+                $body
+            }
+        }
+
+        impl<T> $trait<&Matrix<T>> for MatrixView<'_, T>
+        where
+            T: $($bound)*,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: &Matrix<T>) -> Self::Output {
+                self.$method(rhs.view())
+            }
+        }
+
+        impl<T> $trait<Matrix<T>> for MatrixView<'_, T>
+        where
+            T: $($bound)*,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: Matrix<T>) -> Self::Output {
+                self.$method(rhs.view())
+            }
+        }
+
+        impl<T> $trait<MatrixView<'_, T>> for &Matrix<T>
+        where
+            T: $($bound)*,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: MatrixView<T>) -> Self::Output {
+                self.view().$method(rhs)
+            }
+        }
+
+        impl<T> $trait<MatrixView<'_, T>> for Matrix<T>
+        where
+            T: $($bound)*,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, rhs: MatrixView<T>) -> Self::Output {
+                self.view().$method(rhs)
+            }
+        }
+    };
+}
+
+defviewop!(Add, add, (Add<Output = T> + Copy), |x, y| x
+    .zip_with(&y, |a, b| a + b));
+defviewop!(Sub, sub, (Sub<Output = T> + Copy), |x, y| x
+    .zip_with(&y, |a, b| a - b));
+defviewop!(Mul, mul, (Element + One), |x, y| {
+    assert_eq!(x.cols, y.rows);
+    let mut ret = Matrix {
+        rows: x.rows,
+        cols: y.cols,
+        array: vec![T::zero(); x.rows * y.cols],
+    };
+    gemm_acc(T::one(), x.operand(), y.operand(), &mut ret.array, y.cols);
+    ret
+});